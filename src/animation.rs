@@ -0,0 +1,296 @@
+//! Animated textures, expanded from their `.png.mcmeta` into frames a palette can play.
+
+use std::{collections::HashMap, time::Duration};
+
+use image::{RgbaImage, imageops};
+
+use crate::{
+    error::{Diagnostics, Error},
+    palette::{BlockTexture, FaceTexture},
+    schema::{location::ResourceLocation, mcmeta::TextureMeta},
+    source::AssetSource,
+    textures::read_texture_image,
+};
+
+/// Length of a game tick, the unit of animation frame times.
+pub const TICK: Duration = Duration::from_millis(50);
+
+/// Most frames a frame of an `interpolate` animation is blended into the next over.
+pub const MAX_INTERPOLATION_STEPS: u32 = 8;
+
+/// Blocks whose animated faces together take more frames than this are left static,
+/// showing the first frame of each face, and reported as [`Error::TooManyAnimationFrames`].
+pub const MAX_ANIMATION_FRAMES: u64 = 256;
+
+/// Animation of one texture.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    /// Texture of each frame in play order with the ticks it shows. Frames are named
+    /// `<texture>/<n>`, numbered in order of first appearance.
+    pub frames: Vec<(ResourceLocation, u32)>,
+}
+
+impl Animation {
+    /// Ticks one loop of the animation takes.
+    pub fn ticks(&self) -> u64 {
+        self.frames.iter().map(|(_, t)| *t as u64).sum()
+    }
+
+    /// Longest time every frame time is a multiple of, in ticks.
+    pub fn unit(&self) -> u64 {
+        self.frames.iter().fold(0, |g, (_, t)| gcd(g, *t as u64))
+    }
+
+    /// Frame showing `tick` ticks after the animation started.
+    pub fn frame_at(&self, tick: u64) -> &ResourceLocation {
+        let mut tick = tick % self.ticks();
+        for (frame, ticks) in &self.frames {
+            if tick < *ticks as u64 {
+                return frame;
+            }
+            tick -= *ticks as u64;
+        }
+        unreachable!("tick is less than the sum of frame times")
+    }
+}
+
+/// Animations of textures, each read from the asset source once.
+#[derive(Default)]
+pub struct Animations {
+    animations: HashMap<ResourceLocation, Option<Animation>>,
+    images: HashMap<ResourceLocation, RgbaImage>,
+}
+
+impl Animations {
+    /// Animation of `texture`, `None` if it has no `.mcmeta` with an animation.
+    ///
+    /// Textures that cannot be read or whose animation is invalid are reported to
+    /// `diagnostics` and treated as static.
+    pub fn get(
+        &mut self,
+        texture: &ResourceLocation,
+        source: &dyn AssetSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<&Animation> {
+        if !self.animations.contains_key(texture) {
+            let animation = match self.load(texture, source) {
+                Ok(animation) => animation,
+                Err(e) => {
+                    diagnostics.push(e);
+                    None
+                }
+            };
+            self.animations.insert(texture.clone(), animation);
+        }
+        self.animations[texture].as_ref()
+    }
+
+    /// Frames of `texture` with the animations of all its faces playing together, and the
+    /// time each frame shows. `None` if no face is animated.
+    ///
+    /// Animated faces keep their rotation, mirroring and UV region in every frame. More than
+    /// [`MAX_ANIMATION_FRAMES`] frames are reported to `diagnostics` and cut to the first.
+    pub fn animate(
+        &mut self,
+        texture: &BlockTexture,
+        source: &dyn AssetSource,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<(Vec<BlockTexture>, Duration)> {
+        for face in texture.faces() {
+            self.get(&face.path, source, diagnostics);
+        }
+
+        let animated: Vec<(&ResourceLocation, &Animation)> = texture
+            .faces()
+            .into_iter()
+            .filter_map(|f| Some((&f.path, self.animations[&f.path].as_ref()?)))
+            .collect();
+        if animated.is_empty() {
+            return None;
+        }
+
+        let unit = animated.iter().fold(0, |g, (_, a)| gcd(g, a.unit()));
+        let ticks = animated.iter().try_fold(1, |l: u64, (_, a)| {
+            l.checked_mul(a.ticks() / gcd(l, a.ticks()))
+        });
+
+        let frame = |tick: u64| {
+            texture.map_faces(|f| match &self.animations[&f.path] {
+                Some(animation) => FaceTexture {
+                    path: animation.frame_at(tick).clone(),
+                    ..f.clone()
+                },
+                None => f.clone(),
+            })
+        };
+
+        match ticks.map(|t| t / unit) {
+            Some(count) if count <= MAX_ANIMATION_FRAMES => Some((
+                (0..count).map(|i| frame(i * unit)).collect(),
+                TICK * unit as u32,
+            )),
+            _ => {
+                let mut textures: Vec<ResourceLocation> = Vec::new();
+                for (texture, _) in &animated {
+                    if !textures.contains(texture) {
+                        textures.push((*texture).clone());
+                    }
+                }
+                diagnostics.push(Error::TooManyAnimationFrames {
+                    textures,
+                    max_frames: MAX_ANIMATION_FRAMES,
+                });
+                Some((vec![frame(0)], TICK * unit as u32))
+            }
+        }
+    }
+
+    /// Image of an animation frame returned by [`Animations::animate`].
+    pub fn image(&self, frame: &ResourceLocation) -> Option<&RgbaImage> {
+        self.images.get(frame)
+    }
+
+    fn load(
+        &mut self,
+        texture: &ResourceLocation,
+        source: &dyn AssetSource,
+    ) -> Result<Option<Animation>, Error> {
+        let meta_path = format!("{}.mcmeta", texture.asset_path("textures", "png"));
+        if !source.exists(&meta_path) {
+            return Ok(None);
+        }
+
+        let json = source.read_to_string(&meta_path).map_err(|e| Error::Io {
+            path: meta_path.clone(),
+            source: e,
+        })?;
+        let meta: TextureMeta =
+            serde_json::from_str(&json).map_err(|e| Error::json(&meta_path, e))?;
+        let Some(meta) = meta.animation else {
+            return Ok(None);
+        };
+
+        let image = read_texture_image(texture, source)?;
+
+        let invalid = |message: String| Error::InvalidAnimation {
+            texture: texture.clone(),
+            message,
+        };
+
+        let (width, height) = image.dimensions();
+        // Like the game, frames without a size are square, and a missing side spans the image
+        let (frame_width, frame_height) = match (meta.width, meta.height) {
+            (None, None) => (width.min(height), width.min(height)),
+            (frame_width, frame_height) => {
+                (frame_width.unwrap_or(width), frame_height.unwrap_or(height))
+            }
+        };
+        if frame_width == 0
+            || frame_height == 0
+            || width % frame_width != 0
+            || height % frame_height != 0
+        {
+            return Err(invalid(format!(
+                "frames of {}x{} pixels do not tile the {}x{} image",
+                frame_width, frame_height, width, height
+            )));
+        }
+        let columns = width / frame_width;
+        let frame_count = columns * (height / frame_height);
+
+        let keyframes: Vec<(u32, u32)> = match &meta.frames {
+            Some(frames) => frames
+                .iter()
+                .map(|f| (f.index(), f.time(meta.frametime)))
+                .collect(),
+            None => (0..frame_count).map(|i| (i, meta.frametime)).collect(),
+        };
+        if keyframes.is_empty() {
+            return Err(invalid("no frames".to_owned()));
+        }
+        if let Some((index, _)) = keyframes.iter().find(|(i, _)| *i >= frame_count) {
+            return Err(invalid(format!(
+                "frame {} but the image has {} frames",
+                index, frame_count
+            )));
+        }
+        if keyframes.iter().any(|(_, time)| *time == 0) {
+            return Err(invalid("frame time of 0 ticks".to_owned()));
+        }
+
+        let crop = |index: u32| {
+            let (x, y) = (index % columns, index / columns);
+            imageops::crop_imm(
+                &image,
+                x * frame_width,
+                y * frame_height,
+                frame_width,
+                frame_height,
+            )
+            .to_image()
+        };
+
+        // Each step blends keyframe `from` into `to` by `step / steps`
+        let mut steps = Vec::new();
+        for (i, &(from, time)) in keyframes.iter().enumerate() {
+            let to = keyframes[(i + 1) % keyframes.len()].0;
+            let n = if meta.interpolate && to != from {
+                interpolation_steps(time)
+            } else {
+                1
+            };
+            for step in 0..n {
+                let key = if step == 0 {
+                    (from, from, 0, 1)
+                } else {
+                    (from, to, step, n)
+                };
+                steps.push((key, time / n));
+            }
+        }
+
+        let mut names: HashMap<(u32, u32, u32, u32), ResourceLocation> = HashMap::new();
+        let mut frames = Vec::with_capacity(steps.len());
+        for (key, ticks) in steps {
+            if !names.contains_key(&key) {
+                let name = ResourceLocation::new(
+                    texture.namespace.as_str(),
+                    format!("{}/{}", texture.path, names.len()),
+                );
+                let (from, to, step, n) = key;
+                let frame = if step == 0 {
+                    crop(from)
+                } else {
+                    blend(&crop(from), &crop(to), step as f32 / n as f32)
+                };
+                self.images.insert(name.clone(), frame);
+                names.insert(key, name);
+            }
+            frames.push((names[&key].clone(), ticks));
+        }
+
+        Ok(Some(Animation { frames }))
+    }
+}
+
+/// Largest number of steps up to [`MAX_INTERPOLATION_STEPS`] that `time` ticks divide into
+/// evenly.
+fn interpolation_steps(time: u32) -> u32 {
+    (1..=MAX_INTERPOLATION_STEPS.min(time))
+        .rev()
+        .find(|n| time.is_multiple_of(*n))
+        .unwrap_or(1)
+}
+
+fn blend(a: &RgbaImage, b: &RgbaImage, t: f32) -> RgbaImage {
+    RgbaImage::from_fn(a.width(), a.height(), |x, y| {
+        let (pa, pb) = (a.get_pixel(x, y), b.get_pixel(x, y));
+        image::Rgba(
+            [0, 1, 2, 3].map(|c| (pa[c] as f32 * (1.0 - t) + pb[c] as f32 * t).round() as u8),
+        )
+    })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
@@ -0,0 +1,250 @@
+//! Images turned into block art: a grid of palette materials that shows the image when
+//! seen from one side.
+//!
+//! Each pixel becomes one block, the material whose face toward the viewer is closest in
+//! color, optionally dithered so areas between two materials mix them.
+
+use std::collections::HashMap;
+
+use image::{RgbaImage, imageops};
+use serde::Serialize;
+
+use crate::{
+    colors::TextureImages,
+    error::Error,
+    matching::{ColorIndex, ColorMatch, MatchFace},
+    palette::{BlockTexture, Color, FaceTexture, MaterialDisplay, Palette},
+    textures::render_face,
+};
+
+/// Pixels less opaque than this are left without a block.
+pub const MIN_ALPHA: u8 = 128;
+
+/// Side of the square each block takes in a preview, in pixels.
+pub const PREVIEW_TILE: u32 = 16;
+
+/// Largest offset ordered dithering adds to a channel, in either direction.
+const ORDERED_SPREAD: f32 = 16.0;
+
+/// Thresholds of ordered dithering, each 4×4 block of pixels getting every one once.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Direction block art faces, toward the viewer.
+///
+/// The image keeps its orientation as seen from that side: walls have the top of the image
+/// up, a floor seen from above has it north and a ceiling seen from below has it south.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Facing {
+    /// A floor seen from above
+    Up,
+    /// A ceiling seen from below
+    Down,
+    North,
+    /// A wall seen from the south
+    #[default]
+    South,
+    East,
+    West,
+}
+
+impl Facing {
+    /// Face of the blocks the viewer sees.
+    pub fn face(self) -> MatchFace {
+        match self {
+            Facing::Up => MatchFace::Top,
+            Facing::Down => MatchFace::Bottom,
+            Facing::North => MatchFace::North,
+            Facing::South => MatchFace::South,
+            Facing::East => MatchFace::East,
+            Facing::West => MatchFace::West,
+        }
+    }
+
+    pub fn face_texture(self, texture: &BlockTexture) -> &FaceTexture {
+        match self {
+            Facing::Up => &texture.y,
+            Facing::Down => &texture.ny,
+            Facing::North => &texture.z,
+            Facing::South => &texture.nz,
+            Facing::East => &texture.x,
+            Facing::West => &texture.nx,
+        }
+    }
+
+    /// Size along x, y and z of art `width` blocks wide and `height` blocks high.
+    pub fn size(self, width: usize, height: usize) -> [usize; 3] {
+        match self {
+            Facing::Up | Facing::Down => [width, 1, height],
+            Facing::North | Facing::South => [width, height, 1],
+            Facing::East | Facing::West => [1, height, width],
+        }
+    }
+
+    /// Position along x, y and z of the block in `column` and `row` of art `width` blocks
+    /// wide and `height` blocks high, counting from its top left corner.
+    pub fn position(self, column: usize, row: usize, width: usize, height: usize) -> [usize; 3] {
+        let (right, left, down, up) = (column, width - 1 - column, row, height - 1 - row);
+        match self {
+            Facing::Up => [right, 0, down],
+            Facing::Down => [right, 0, up],
+            Facing::North => [left, up, 0],
+            Facing::South => [right, up, 0],
+            Facing::East => [0, up, left],
+            Facing::West => [0, up, right],
+        }
+    }
+}
+
+/// How the difference between a pixel and its block is spread to the pixels around it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Every pixel gets its closest block
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion, to the right and the row below
+    FloydSteinberg,
+    /// Bayer matrix offsets, a regular pattern that keeps flat areas stable
+    Ordered,
+}
+
+/// Materials making up an image, see [`block_art`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BlockArt {
+    pub width: usize,
+    pub height: usize,
+    pub facing: Facing,
+    /// Material id of each block, row by row from the top as seen by the viewer. `None`
+    /// where the image is transparent
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+/// Art showing `image` with one block per pixel out of the materials in `index`, which
+/// should match by the face of `facing`.
+///
+/// Pixels less opaque than [`MIN_ALPHA`] have no block, and neither does any pixel if
+/// `index` is empty.
+pub fn block_art(
+    image: &RgbaImage,
+    index: &ColorIndex,
+    facing: Facing,
+    dither: Dither,
+) -> BlockArt {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut matches: HashMap<Color, Option<ColorMatch>> = HashMap::new();
+    let mut nearest = |color: Color| *matches.entry(color).or_insert_with(|| index.nearest(color));
+
+    // Color each pixel still has to show, with the errors diffused to it so far
+    let mut wanted: Vec<[f32; 3]> = image
+        .pixels()
+        .map(|p| [p[0], p[1], p[2]].map(f32::from))
+        .collect();
+
+    let mut rows = vec![vec![None; width]; height];
+    for (y, row) in rows.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let alpha = image.get_pixel(x as u32, y as u32)[3];
+            if alpha < MIN_ALPHA {
+                continue;
+            }
+
+            let mut rgb = wanted[y * width + x];
+            if dither == Dither::Ordered {
+                let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0;
+                rgb = rgb.map(|c| c + (threshold * 2.0 - 1.0) * ORDERED_SPREAD);
+            }
+            let rgb = rgb.map(|c| c.round().clamp(0.0, 255.0));
+            let [r, g, b] = rgb.map(|c| c as u8);
+
+            let Some(matched) = nearest(Color { r, g, b, a: alpha }) else {
+                continue;
+            };
+            *cell = Some(matched.id.to_owned());
+
+            if dither == Dither::FloydSteinberg {
+                let shown = [matched.color.r, matched.color.g, matched.color.b];
+                let error: [f32; 3] = [0, 1, 2].map(|c| rgb[c] - shown[c] as f32);
+                let neighbors = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+                for (dx, dy, weight) in neighbors {
+                    let (nx, ny) = (x as isize + dx, y + dy);
+                    if nx < 0 || nx as usize >= width || ny >= height {
+                        continue;
+                    }
+                    let target = &mut wanted[ny * width + nx as usize];
+                    for (t, e) in target.iter_mut().zip(error) {
+                        *t += e * weight / 16.0;
+                    }
+                }
+            }
+        }
+    }
+
+    BlockArt {
+        width,
+        height,
+        facing,
+        rows,
+    }
+}
+
+/// Picture of `art` with the face of each block toward the viewer, [`PREVIEW_TILE`]
+/// pixels per block, read from the textures in `images`.
+///
+/// Animated textures show their first frame. Volumes are drawn in the color of their face
+/// as a flat tile, and blocks whose material is missing from `palette` are left empty.
+pub fn render_preview(
+    art: &BlockArt,
+    palette: &Palette,
+    images: &mut TextureImages,
+) -> Result<RgbaImage, Error> {
+    let mut preview = RgbaImage::new(
+        art.width as u32 * PREVIEW_TILE,
+        art.height as u32 * PREVIEW_TILE,
+    );
+    let mut tiles: HashMap<&str, RgbaImage> = HashMap::new();
+
+    for (y, row) in art.rows.iter().enumerate() {
+        for (x, id) in row.iter().enumerate() {
+            let Some(id) = id else {
+                continue;
+            };
+            if !tiles.contains_key(id.as_str()) {
+                let Some(material) = palette.materials.get(id) else {
+                    continue;
+                };
+                let texture = match &material.display {
+                    MaterialDisplay::Texture(texture) => Some(texture),
+                    MaterialDisplay::TextureAnimation { frames, .. } => frames.first(),
+                    _ => None,
+                };
+                let tile = match texture {
+                    Some(texture) => {
+                        let face = art.facing.face_texture(texture);
+                        let pixels = render_face(face, images.get(&face.path)?);
+                        imageops::resize(
+                            &pixels,
+                            PREVIEW_TILE,
+                            PREVIEW_TILE,
+                            imageops::FilterType::Nearest,
+                        )
+                    }
+                    None => {
+                        let color = material.colors.as_ref().map(|c| art.facing.face().color(c));
+                        let pixel = color.map_or([0; 4], |c| [c.r, c.g, c.b, c.a]);
+                        RgbaImage::from_pixel(PREVIEW_TILE, PREVIEW_TILE, image::Rgba(pixel))
+                    }
+                };
+                tiles.insert(id, tile);
+            }
+
+            imageops::replace(
+                &mut preview,
+                &tiles[id.as_str()],
+                (x as u32 * PREVIEW_TILE) as i64,
+                (y as u32 * PREVIEW_TILE) as i64,
+            );
+        }
+    }
+
+    Ok(preview)
+}
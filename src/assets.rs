@@ -0,0 +1,617 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io,
+    path::Path,
+};
+
+use image::RgbaImage;
+use indexmap::IndexMap;
+
+use crate::{
+    animation::Animations,
+    baked::{self, BakedModel},
+    colors::{self, TextureImages},
+    cubes::{get_all_empty_blocks, get_all_full_cube_blocks},
+    error::{Diagnostics, Error},
+    java,
+    palette::{
+        BlockIds, BlockTexture, BlockVolume, FaceTexture, Group, GroupRule, Material,
+        MaterialDisplay,
+    },
+    registry::BlockRegistry,
+    schema::{
+        blockstate::{self, BlockState, ModelDefinition, ModelVariant},
+        location::ResourceLocation,
+        model::{self, Model, TextureRef},
+    },
+    shapes::{self, Cuboid, Shape},
+    source::{self, LayeredSource},
+    textures::{copy_textures_from_materials, get_block_textures, model_rotation},
+    tint::Tints,
+    variants::{BlockVariant, get_all_block_states, get_all_block_variants},
+    voxel::{self, VoxelGrid},
+};
+
+/// Blocks the game renders as fluids, whose models have no elements.
+const FLUIDS: [&str; 2] = ["minecraft:water", "minecraft:lava"];
+
+/// Materials of a palette together with the groups that tie them together.
+#[derive(Default)]
+pub struct MaterialSet {
+    pub materials: IndexMap<String, Material>,
+    pub groups: IndexMap<String, Group>,
+    /// Voxel grids of the volume materials, keyed by [`BlockVolume::path`]
+    pub volumes: IndexMap<String, VoxelGrid>,
+    /// Images of the animation frames the materials show, see [`crate::animation`]
+    pub frames: IndexMap<ResourceLocation, RgbaImage>,
+}
+
+impl MaterialSet {
+    /// Adds the materials, groups, volumes and frames of `other`, replacing those with the same ids.
+    pub fn extend(&mut self, other: MaterialSet) {
+        self.materials.extend(other.materials);
+        self.groups.extend(other.groups);
+        self.volumes.extend(other.volumes);
+        self.frames.extend(other.frames);
+    }
+
+    /// Writes every volume to `output_dir/<path>.voxel`, returning how many were written.
+    pub fn save_volumes(&self, output_dir: &Path) -> io::Result<usize> {
+        for (path, grid) in &self.volumes {
+            grid.save(output_dir.join(format!("{}.{}", path, voxel::VOXEL_EXTENSION)))?;
+        }
+        Ok(self.volumes.len())
+    }
+}
+
+/// Path of the grid of a multipart variant, its blockstate key made safe for file names:
+/// `minecraft/oak_fence/east-true.north-false` for `east=true,north=false`.
+fn multipart_volume_path(block: &ResourceLocation, blockstate_key: &str) -> String {
+    if blockstate_key.is_empty() {
+        return block.file_stem();
+    }
+
+    let key: String = blockstate_key
+        .chars()
+        .map(|c| match c {
+            '=' => '-',
+            ',' => '.',
+            c => c,
+        })
+        .collect();
+    format!("{}/{}", block.file_stem(), key)
+}
+
+/// Blockstates and block models loaded from a stack of asset sources.
+pub struct AssetStore {
+    pub source: LayeredSource,
+    pub blockstates: HashMap<ResourceLocation, BlockState>,
+    pub models: HashMap<ResourceLocation, Model>,
+    /// `models` with their parents merged in
+    pub baked_models: HashMap<ResourceLocation, BakedModel>,
+    /// Block properties and default states from the game sources or a block report
+    pub registry: Option<BlockRegistry>,
+    /// Colors of tinted faces, at [`crate::tint::Climate::PLAINS`] unless changed
+    pub tints: Tints,
+}
+
+impl AssetStore {
+    /// Opens client jars, resource pack zips or extracted directories containing `assets/`.
+    ///
+    /// Later paths override earlier ones, so vanilla goes first and resource packs after.
+    pub fn open<P: AsRef<Path>>(paths: &[P], diagnostics: &mut Diagnostics) -> io::Result<Self> {
+        Self::load(source::open_layered(paths)?, diagnostics)
+    }
+
+    pub fn load(source: LayeredSource, diagnostics: &mut Diagnostics) -> io::Result<Self> {
+        let blockstates = blockstate::load_all(&source, diagnostics)?;
+        let models = model::load_all(&source, diagnostics)?;
+        let baked_models = baked::bake_all(&models, diagnostics);
+        let tints = Tints::load(&source, diagnostics);
+
+        Ok(Self {
+            source,
+            blockstates,
+            models,
+            baked_models,
+            registry: None,
+            tints,
+        })
+    }
+
+    /// Reads block properties from decompiled game sources under `src_root`.
+    ///
+    /// Afterwards [`AssetStore::block_states`] lists every state the game registers.
+    pub fn load_java_registry<P: AsRef<Path>>(
+        &mut self,
+        src_root: P,
+        diagnostics: &mut Diagnostics,
+    ) -> io::Result<()> {
+        self.registry = Some(java::load_block_registry(src_root, diagnostics)?);
+        Ok(())
+    }
+
+    /// Reads block properties from a data generator block report, as an alternative to
+    /// [`AssetStore::load_java_registry`].
+    pub fn load_blocks_report<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        self.registry = Some(BlockRegistry::load_blocks_report(path)?);
+        Ok(())
+    }
+
+    /// Models applied for `block_name` in `state`, see [`BlockState::applied_models`].
+    ///
+    /// Returns `None` if the block has no blockstate.
+    pub fn applied_models(
+        &self,
+        block_name: &ResourceLocation,
+        state: &BTreeMap<String, String>,
+    ) -> Option<Vec<&ModelVariant>> {
+        self.blockstates
+            .get(block_name)
+            .map(|b| b.applied_models(state))
+    }
+
+    /// Variants named by the blockstate files, one per variant key or multipart combination.
+    pub fn block_variants(&self) -> Vec<BlockVariant> {
+        get_all_block_variants(&self.blockstates, self.registry.as_ref())
+    }
+
+    /// Every state of every block, exact for blocks in the registry and inferred from the
+    /// blockstate files otherwise.
+    pub fn block_states(&self) -> Vec<BlockVariant> {
+        match &self.registry {
+            Some(registry) => get_all_block_states(&self.blockstates, registry),
+            None => self.block_variants(),
+        }
+    }
+
+    /// Shape of every block state, keyed by block id.
+    pub fn block_shapes(&self, diagnostics: &mut Diagnostics) -> IndexMap<String, Shape> {
+        self.block_states()
+            .into_iter()
+            .map(|v| (v.id(), self.variant_shape(&v, diagnostics)))
+            .collect()
+    }
+
+    /// Classifies the elements of every model applied for `variant`, placed as its
+    /// blockstate rotates them.
+    ///
+    /// Of weighted alternatives only the first model is considered.
+    pub fn variant_shape(&self, variant: &BlockVariant, diagnostics: &mut Diagnostics) -> Shape {
+        let state = blockstate::parse_state(variant.blockstate.as_deref().unwrap_or_default());
+        let mut elements = Vec::new();
+
+        for part in self
+            .applied_models(&variant.name, &state)
+            .unwrap_or_default()
+        {
+            let Some(m) = part.models().into_iter().next() else {
+                continue;
+            };
+            let Some(model) = self.baked_models.get(&m.model) else {
+                diagnostics.push(Error::MissingModel {
+                    model: m.model.clone(),
+                    referenced_by: variant.name.to_string(),
+                });
+                return Shape::Other;
+            };
+            let rotation = match model_rotation(m, &variant.name.to_string()) {
+                Ok(r) => r,
+                Err(e) => {
+                    diagnostics.push(e);
+                    return Shape::Other;
+                }
+            };
+
+            elements.extend(
+                model
+                    .elements
+                    .iter()
+                    .map(|e| (Cuboid::of(e).rotated(rotation), e.rotation.as_ref())),
+            );
+        }
+
+        shapes::classify(&elements)
+    }
+
+    pub fn full_cube_blocks(&self, diagnostics: &mut Diagnostics) -> HashSet<ResourceLocation> {
+        get_all_full_cube_blocks(&self.blockstates, &self.baked_models, diagnostics)
+    }
+
+    pub fn empty_blocks(&self, diagnostics: &mut Diagnostics) -> HashSet<ResourceLocation> {
+        get_all_empty_blocks(&self.blockstates, &self.baked_models, diagnostics)
+    }
+
+    /// Textures of every weighted model of a variant, with the tint of the block on the
+    /// faces with a `tintindex`.
+    pub fn block_textures(
+        &self,
+        block_name: &ResourceLocation,
+        blockstate_key: &str,
+    ) -> Result<Vec<BlockTexture>, Error> {
+        let tint = self
+            .tints
+            .color(block_name, &blockstate::parse_state(blockstate_key));
+
+        get_block_textures(
+            block_name,
+            blockstate_key,
+            &self.baked_models,
+            &self.blockstates,
+            tint,
+        )
+    }
+
+    /// Textured materials for every variant of every full cube block, keyed by block id.
+    ///
+    /// Water and lava are included too, showing the still texture of the fluid on every face,
+    /// except for variants of a flowing `level` other than 0.
+    /// With `default_only` only the default variant of each block is included, which needs
+    /// a registry to know the defaults of blocks with properties.
+    /// A variant with several weighted models gets one material per model, with ids
+    /// `<block id>@<index>`, and a weighted random group under the block id.
+    /// Variants whose textures cannot be resolved are left out and reported to `diagnostics`.
+    pub fn full_cube_materials(
+        &self,
+        default_only: bool,
+        diagnostics: &mut Diagnostics,
+    ) -> MaterialSet {
+        let full_cube_blocks = self.full_cube_blocks(diagnostics);
+        let mut set = MaterialSet::default();
+        let mut animations = Animations::default();
+
+        for v in self.block_variants() {
+            if default_only && !v.default {
+                continue;
+            }
+            let blockstate_key = v.blockstate.as_deref().unwrap_or_default();
+
+            if FLUIDS.contains(&v.name.to_string().as_str()) {
+                // Vanilla fluids have a single variant for every level
+                let level = blockstate::parse_state(blockstate_key).remove("level");
+                if level.is_none_or(|l| l == "0") {
+                    match self.fluid_texture(&v.name, blockstate_key) {
+                        Ok(texture) => {
+                            let material = self.texture_material(
+                                texture,
+                                &mut animations,
+                                &mut set.frames,
+                                diagnostics,
+                            );
+                            set.materials.insert(v.id(), material);
+                        }
+                        Err(e) => diagnostics.push(e),
+                    }
+                }
+                continue;
+            }
+            if !full_cube_blocks.contains(&v.name) {
+                continue;
+            }
+
+            let textures = match self.block_textures(&v.name, blockstate_key) {
+                Ok(t) => t,
+                Err(e) => {
+                    diagnostics.push(e);
+                    continue;
+                }
+            };
+
+            let block_id = v.id();
+
+            if textures.len() == 1 {
+                let texture = textures.into_iter().next().unwrap();
+                let material =
+                    self.texture_material(texture, &mut animations, &mut set.frames, diagnostics);
+                set.materials.insert(block_id, material);
+                continue;
+            }
+
+            let model_defs = self.blockstates[&v.name].variants[blockstate_key].models();
+            let mut weights = BTreeMap::new();
+
+            for (i, (texture, m)) in textures.into_iter().zip(model_defs).enumerate() {
+                let material_id = format!("{}@{}", block_id, i);
+
+                // The game rejects such blockstates; 1 keeps the model a possible choice
+                if m.weight <= 0 {
+                    diagnostics.push(Error::InvalidWeight {
+                        model: m.model.clone(),
+                        referenced_by: block_id.clone(),
+                        weight: m.weight,
+                    });
+                }
+                weights.insert(material_id.clone(), m.weight.max(1) as u32);
+                let material =
+                    self.texture_material(texture, &mut animations, &mut set.frames, diagnostics);
+                set.materials.insert(material_id, material);
+            }
+
+            set.groups.insert(
+                block_id,
+                Group {
+                    block_ids: BlockIds::Blocks(weights.keys().cloned().collect()),
+                    rule: GroupRule::RandomChoice { weights },
+                },
+            );
+        }
+
+        set
+    }
+
+    /// Voxelized materials for every variant of the blocks that are not full cubes, keyed by
+    /// block id, with grids of `size`³ voxels.
+    ///
+    /// Variants made of a single model share the grid of that model, with the blockstate
+    /// rotation on the [`BlockVolume`]. Multipart variants get a grid of their own, under
+    /// a path made from the block and its state that needs no escaping as a file name.
+    /// Volumes with tinted voxel faces carry the tint of their block and state.
+    /// Of weighted alternatives only the first model is used, and empty grids are left out.
+    pub fn volume_materials(
+        &self,
+        size: usize,
+        default_only: bool,
+        diagnostics: &mut Diagnostics,
+    ) -> MaterialSet {
+        let full_cube_blocks = self.full_cube_blocks(diagnostics);
+        let mut set = MaterialSet::default();
+
+        for v in self.block_variants() {
+            if full_cube_blocks.contains(&v.name) || (default_only && !v.default) {
+                continue;
+            }
+
+            let blockstate_key = v.blockstate.as_deref().unwrap_or_default();
+            let state = blockstate::parse_state(blockstate_key);
+            let parts: Vec<&ModelDefinition> = self
+                .applied_models(&v.name, &state)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|p| p.models().into_iter().next())
+                .collect();
+
+            let (mut volume, shared) = if let [m] = parts[..] {
+                let [x, y, z] = match model_rotation(m, &v.name.to_string()) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        diagnostics.push(e);
+                        continue;
+                    }
+                };
+                let volume = BlockVolume {
+                    path: m.model.file_stem(),
+                    rotation_x: x,
+                    rotation_y: y,
+                    rotation_z: z,
+                    ..Default::default()
+                };
+                (volume, true)
+            } else {
+                let volume = BlockVolume {
+                    path: multipart_volume_path(&v.name, blockstate_key),
+                    ..Default::default()
+                };
+                (volume, false)
+            };
+
+            if !set.volumes.contains_key(&volume.path) {
+                let grid = if shared {
+                    self.voxelize_model(&v.name, parts[0], size)
+                } else {
+                    self.voxelize_parts(&v.name, &parts, size)
+                };
+
+                match grid {
+                    Ok(grid) if !grid.is_empty() => {
+                        set.volumes.insert(volume.path.clone(), grid);
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        diagnostics.push(e);
+                        continue;
+                    }
+                }
+            }
+
+            let grid = &set.volumes[&volume.path];
+            if grid.is_tinted() {
+                volume.tint = self.tints.color(&v.name, &state);
+            }
+
+            let packs = self.packs(grid.textures.iter());
+            set.materials.insert(
+                v.id(),
+                Material {
+                    display: MaterialDisplay::Volume(volume),
+                    profile: None,
+                    packs,
+                    colors: None,
+                },
+            );
+        }
+
+        set
+    }
+
+    /// Voxelizes the model of `m`, leaving out its blockstate rotation.
+    fn voxelize_model(
+        &self,
+        block_name: &ResourceLocation,
+        m: &ModelDefinition,
+        size: usize,
+    ) -> Result<VoxelGrid, Error> {
+        let model = self
+            .baked_models
+            .get(&m.model)
+            .ok_or_else(|| Error::MissingModel {
+                model: m.model.clone(),
+                referenced_by: block_name.to_string(),
+            })?;
+
+        voxel::voxelize(model, size)
+    }
+
+    /// Voxelizes the models of `parts` into one grid, each placed as its blockstate rotates it.
+    fn voxelize_parts(
+        &self,
+        block_name: &ResourceLocation,
+        parts: &[&ModelDefinition],
+        size: usize,
+    ) -> Result<VoxelGrid, Error> {
+        let mut grid = VoxelGrid::new(size);
+
+        for m in parts {
+            let rotation = model_rotation(m, &block_name.to_string())?;
+            let part = self.voxelize_model(block_name, m, size)?;
+            grid.merge(&part.rotated(rotation));
+        }
+
+        Ok(grid)
+    }
+
+    /// Texture of a fluid block showing the `particle` texture of its model on every face,
+    /// which is the still texture of the fluid, with the tint of the block.
+    fn fluid_texture(
+        &self,
+        block_name: &ResourceLocation,
+        blockstate_key: &str,
+    ) -> Result<BlockTexture, Error> {
+        let state = blockstate::parse_state(blockstate_key);
+        let m = self
+            .applied_models(block_name, &state)
+            .ok_or_else(|| Error::MissingBlockstate {
+                block: block_name.clone(),
+            })?
+            .into_iter()
+            .find_map(|p| p.models().into_iter().next())
+            .ok_or_else(|| Error::MissingVariant {
+                block: block_name.clone(),
+                key: blockstate_key.to_owned(),
+            })?;
+        let model = self
+            .baked_models
+            .get(&m.model)
+            .ok_or_else(|| Error::MissingModel {
+                model: m.model.clone(),
+                referenced_by: block_name.to_string(),
+            })?;
+        let Some(TextureRef::Location(still)) = model.textures.get("particle") else {
+            return Err(Error::UnresolvedTexture {
+                model: m.model.clone(),
+                variable: "particle".to_owned(),
+            });
+        };
+
+        let face = FaceTexture::new(still.clone()).with_tint(self.tints.color(block_name, &state));
+        Ok(BlockTexture {
+            x: face.clone(),
+            nx: face.clone(),
+            y: face.clone(),
+            ny: face.clone(),
+            z: face.clone(),
+            nz: face,
+        })
+    }
+
+    /// A textured material, animated if any face texture has a `.mcmeta` animation.
+    ///
+    /// Images of the animation frames it shows are added to `frames`.
+    fn texture_material(
+        &self,
+        texture: BlockTexture,
+        animations: &mut Animations,
+        frames: &mut IndexMap<ResourceLocation, RgbaImage>,
+        diagnostics: &mut Diagnostics,
+    ) -> Material {
+        let packs = self.texture_packs(&texture);
+
+        let display = match animations.animate(&texture, &self.source, diagnostics) {
+            None => MaterialDisplay::Texture(texture),
+            Some((animated, delay)) => {
+                for face in animated.iter().flat_map(BlockTexture::faces) {
+                    if let Some(image) = animations.image(&face.path)
+                        && !frames.contains_key(&face.path)
+                    {
+                        frames.insert(face.path.clone(), image.clone());
+                    }
+                }
+
+                match <[BlockTexture; 1]>::try_from(animated) {
+                    Ok([frame]) => MaterialDisplay::Texture(frame),
+                    Err(animated) => MaterialDisplay::TextureAnimation {
+                        frames: animated,
+                        delay,
+                    },
+                }
+            }
+        };
+
+        Material {
+            display,
+            profile: None,
+            packs,
+            colors: None,
+        }
+    }
+
+    /// Copies the block textures referenced by `materials` to `output_dir`.
+    ///
+    /// Returns the number of copied and failed textures.
+    pub fn copy_textures(&self, set: &MaterialSet, output_dir: &Path) -> (usize, usize) {
+        copy_textures_from_materials(
+            set.materials.values(),
+            set.volumes.values(),
+            &set.frames,
+            &self.source,
+            output_dir,
+        )
+    }
+
+    /// Computes the [`crate::palette::MaterialColors`] of every textured or volume material in `set` that
+    /// has none yet, see [`crate::colors`].
+    ///
+    /// Materials whose textures cannot be read are reported to `diagnostics`. Returns the
+    /// number of materials colored.
+    pub fn add_colors(&self, set: &mut MaterialSet, diagnostics: &mut Diagnostics) -> usize {
+        let mut images = TextureImages::assets(&self.source, &set.frames);
+        let volumes = &set.volumes;
+
+        colors::add_colors(
+            set.materials.iter_mut(),
+            &mut images,
+            &mut |path| {
+                volumes
+                    .get(path)
+                    .cloned()
+                    .ok_or_else(|| Error::MissingVolume {
+                        path: path.to_owned(),
+                    })
+            },
+            diagnostics,
+        )
+    }
+
+    /// Names of the layers the faces of `texture` are read from, in stacking order.
+    pub fn texture_packs(&self, texture: &BlockTexture) -> Vec<String> {
+        let faces = [
+            &texture.x,
+            &texture.nx,
+            &texture.y,
+            &texture.ny,
+            &texture.z,
+            &texture.nz,
+        ];
+
+        self.packs(faces.iter().map(|f| &f.path))
+    }
+
+    /// Names of the layers `textures` are read from, in stacking order.
+    fn packs<'a>(&self, textures: impl Iterator<Item = &'a ResourceLocation>) -> Vec<String> {
+        let layers: BTreeSet<usize> = textures
+            .filter_map(|t| self.source.provider_index(&t.asset_path("textures", "png")))
+            .collect();
+
+        let names: Vec<&str> = self.source.layer_names().collect();
+        layers.into_iter().map(|i| names[i].to_owned()).collect()
+    }
+}
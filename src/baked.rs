@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    error::{Diagnostics, Error},
+    schema::{
+        location::ResourceLocation,
+        model::{DisplayTransform, Element, Face, Model, TextureRef},
+    },
+};
+
+/// Parent chains longer than this are cut off, which only happens with broken packs.
+pub const MAX_PARENT_DEPTH: usize = 64;
+
+/// A model with its parent chain merged in, as the game sees it when rendering.
+///
+/// Texture variables and face textures are resolved to locations where possible;
+/// those that cannot be resolved stay [`TextureRef::Variable`].
+#[derive(Debug, Clone, Serialize)]
+pub struct BakedModel {
+    pub name: ResourceLocation,
+    pub ambientocclusion: bool,
+    pub display: HashMap<String, DisplayTransform>,
+    pub textures: HashMap<String, TextureRef>,
+    pub elements: Vec<Element>,
+}
+
+impl BakedModel {
+    /// Merges the parents of `name` into it.
+    ///
+    /// Elements of the closest model that has any replace those of its parents, while
+    /// textures and display transforms merge with the child overriding the parent.
+    /// A missing parent, a parent cycle or a chain deeper than [`MAX_PARENT_DEPTH`] is
+    /// reported to `diagnostics` and ends the chain there. So do texture variable cycles,
+    /// which leave the variables unresolved. Built-in parents such as `builtin/generated`
+    /// end the chain without a report, as the game renders those in code.
+    pub fn bake(
+        name: &ResourceLocation,
+        models: &HashMap<ResourceLocation, Model>,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<Self> {
+        let chain = parent_chain(name, models, diagnostics)?;
+
+        let mut baked = BakedModel {
+            name: name.clone(),
+            ambientocclusion: true,
+            display: HashMap::new(),
+            textures: HashMap::new(),
+            elements: Vec::new(),
+        };
+        let mut ambientocclusion = None;
+
+        for model in &chain {
+            ambientocclusion = ambientocclusion.or(model.ambientocclusion);
+
+            for (k, v) in &model.display {
+                baked.display.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+            for (k, v) in &model.textures {
+                baked.textures.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+            if baked.elements.is_empty() {
+                baked.elements = model.elements.clone();
+            }
+        }
+        baked.ambientocclusion = ambientocclusion.unwrap_or(true);
+
+        let textures = baked.textures.clone();
+        for texture in baked.textures.values_mut() {
+            *texture = resolve(texture, name, &textures, diagnostics);
+        }
+        for face in baked.elements.iter_mut().flat_map(|e| e.faces.values_mut()) {
+            face.texture = resolve(&face.texture, name, &textures, diagnostics);
+        }
+
+        Some(baked)
+    }
+
+    /// Whether the model is made only of elements spanning the whole block.
+    pub fn is_full_cube(&self) -> bool {
+        !self.elements.is_empty() && self.elements.iter().all(is_full_cube_element)
+    }
+
+    pub fn full_cube_element(&self) -> Result<&Element, Error> {
+        self.elements
+            .iter()
+            .find(|e| is_full_cube_element(e))
+            .ok_or_else(|| Error::NotCube {
+                model: self.name.clone(),
+            })
+    }
+
+    /// Texture location of `face`, an error if it is a variable no model in the chain defines.
+    pub fn face_texture(&self, face: &Face) -> Result<ResourceLocation, Error> {
+        match &face.texture {
+            TextureRef::Location(location) => Ok(location.clone()),
+            TextureRef::Variable(var) => Err(Error::UnresolvedTexture {
+                model: self.name.clone(),
+                variable: var.clone(),
+            }),
+        }
+    }
+}
+
+/// Bakes every model in `models`, see [`BakedModel::bake`].
+pub fn bake_all(
+    models: &HashMap<ResourceLocation, Model>,
+    diagnostics: &mut Diagnostics,
+) -> HashMap<ResourceLocation, BakedModel> {
+    models
+        .keys()
+        .filter_map(|name| Some((name.clone(), BakedModel::bake(name, models, diagnostics)?)))
+        .collect()
+}
+
+/// `name` followed by its parents, closest first.
+fn parent_chain<'a>(
+    name: &ResourceLocation,
+    models: &'a HashMap<ResourceLocation, Model>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Vec<&'a Model>> {
+    let mut chain = vec![models.get(name)?];
+    let mut names = vec![name];
+
+    while let Some(parent) = &chain[chain.len() - 1].parent {
+        if let Some(start) = names.iter().position(|n| *n == parent) {
+            let mut cycle: Vec<ResourceLocation> =
+                names[start..].iter().map(|&n| n.clone()).collect();
+            cycle.push(parent.clone());
+            diagnostics.push(Error::ParentCycle {
+                chain: normalize_cycle(cycle),
+            });
+            break;
+        }
+        if is_builtin(parent) {
+            break;
+        }
+        if chain.len() >= MAX_PARENT_DEPTH {
+            diagnostics.push(Error::ParentChainTooDeep {
+                model: name.clone(),
+                max_depth: MAX_PARENT_DEPTH,
+            });
+            break;
+        }
+        let Some(model) = models.get(parent) else {
+            diagnostics.push(Error::MissingModel {
+                model: parent.clone(),
+                referenced_by: names[names.len() - 1].to_string(),
+            });
+            break;
+        };
+
+        chain.push(model);
+        names.push(parent);
+    }
+
+    Some(chain)
+}
+
+/// Whether `model` is one the game builds in code, like `builtin/generated` for item
+/// models or `builtin/entity` for blocks drawn by a block entity renderer.
+fn is_builtin(model: &ResourceLocation) -> bool {
+    model.namespace == "minecraft" && model.path.starts_with("builtin/")
+}
+
+/// Follows `#variable` references through `textures` until a location is reached.
+///
+/// Stops at the first variable that is not defined, or reports a cycle to `diagnostics`.
+fn resolve(
+    texture: &TextureRef,
+    model: &ResourceLocation,
+    textures: &HashMap<String, TextureRef>,
+    diagnostics: &mut Diagnostics,
+) -> TextureRef {
+    let mut current = texture;
+    let mut visited: Vec<&String> = Vec::new();
+
+    while let TextureRef::Variable(var) = current {
+        if let Some(start) = visited.iter().position(|v| *v == var) {
+            let mut cycle: Vec<String> = visited[start..].iter().map(|&v| v.clone()).collect();
+            cycle.push(var.clone());
+            diagnostics.push(Error::TextureCycle {
+                model: model.clone(),
+                chain: normalize_cycle(cycle),
+            });
+            break;
+        }
+        let Some(next) = textures.get(var) else {
+            break;
+        };
+
+        visited.push(var);
+        current = next;
+    }
+
+    current.clone()
+}
+
+/// Rotates a cycle like `b -> a -> b` to start at its smallest entry, `a -> b -> a`, so
+/// the same cycle found from different starting points is reported once.
+fn normalize_cycle<T: Ord + Clone>(mut cycle: Vec<T>) -> Vec<T> {
+    cycle.pop();
+    if let Some(min) = (0..cycle.len()).min_by_key(|&i| &cycle[i]) {
+        cycle.rotate_left(min);
+    }
+    if let Some(first) = cycle.first().cloned() {
+        cycle.push(first);
+    }
+    cycle
+}
+
+fn is_full_cube_element(element: &Element) -> bool {
+    element.from == [0.0, 0.0, 0.0]
+        && element.to == [16.0, 16.0, 16.0]
+        && element.rotation.as_ref().is_none_or(|r| r.angle == 0.0)
+}
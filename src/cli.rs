@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use minecraft_blocks::{art, matching, schematic, tint::Climate, voxel::MAX_GRID_SIZE};
+
+#[derive(Parser)]
+#[command(version, about = "Minecraft block data aggregator")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Extract block data and build the palette
+    Extract(ExtractArgs),
+    /// Build only the palette directory
+    Palette(PaletteArgs),
+    /// Check that a serialized palette directory can be loaded
+    Validate(ValidateArgs),
+    /// Convert an image to block art, a grid of palette materials with one block per pixel
+    Art(ArtArgs),
+}
+
+#[derive(Args)]
+pub struct ExtractArgs {
+    #[command(flatten)]
+    pub dirs: DirArgs,
+
+    #[command(flatten)]
+    pub palette: PaletteOptions,
+
+    /// Artifacts to write to the output directory
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = Artifact::all()
+    )]
+    pub emit: Vec<Artifact>,
+}
+
+#[derive(Args)]
+pub struct PaletteArgs {
+    #[command(flatten)]
+    pub dirs: DirArgs,
+
+    #[command(flatten)]
+    pub palette: PaletteOptions,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Palette directory, e.g. `output/minecraft`
+    pub palette_dir: PathBuf,
+}
+
+#[derive(Args)]
+pub struct ArtArgs {
+    /// PNG image to convert
+    pub image: PathBuf,
+
+    /// Palette directory, e.g. `output/minecraft`. Materials without colors get them computed
+    #[arg(long, default_value = "output/minecraft")]
+    pub palette_dir: PathBuf,
+
+    /// JSON file the grid of material ids is written to
+    #[arg(short, long, default_value = "art.json")]
+    pub output: PathBuf,
+
+    /// Also write a PNG of the art rendered with the block textures
+    #[arg(long, value_name = "FILE")]
+    pub preview: Option<PathBuf>,
+
+    /// Also write a Sponge schematic (`.schem`) of the art
+    #[arg(long, value_name = "FILE")]
+    pub schematic: Option<PathBuf>,
+
+    /// Data version of the game version the schematic is for
+    #[arg(long, default_value_t = schematic::DATA_VERSION)]
+    pub data_version: i32,
+
+    /// Scale the image to this many blocks wide first, keeping its aspect ratio unless
+    /// `--height` is also given
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+
+    /// Scale the image to this many blocks high first, keeping its aspect ratio unless
+    /// `--width` is also given
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+
+    /// Direction the art faces, toward the viewer
+    #[arg(long, value_enum, default_value_t = Facing::South)]
+    pub facing: Facing,
+
+    /// How the colors between two materials are approximated
+    #[arg(long, value_enum, default_value_t = Dither::None)]
+    pub dither: Dither,
+
+    /// Color difference formula blocks are matched by
+    #[arg(long, value_enum, default_value_t = DeltaE::Ok)]
+    pub delta_e: DeltaE,
+
+    /// Only use members of this group of the palette. Repeat to allow several groups
+    #[arg(long = "group", value_name = "ID")]
+    pub groups: Vec<String>,
+
+    /// Also use volume materials, which are not full cubes
+    #[arg(long)]
+    pub include_volumes: bool,
+}
+
+#[derive(Args)]
+pub struct DirArgs {
+    /// Client `.jar`, resource pack `.zip` or extracted directory containing `assets/`.
+    /// Repeat to stack resource packs; later ones override earlier ones
+    #[arg(long, default_value = "mc_data/mc_assets")]
+    pub assets: Vec<PathBuf>,
+
+    /// Decompiled game sources (Mojang mappings) to read the exact block states from,
+    /// instead of inferring them from blockstate files
+    #[arg(long, value_name = "DIR", group = "registry")]
+    pub java_src: Option<PathBuf>,
+
+    /// `reports/blocks.json` from the game's data generator, as an alternative to `--java-src`
+    #[arg(long, value_name = "FILE", group = "registry")]
+    pub blocks_report: Option<PathBuf>,
+
+    /// Directory all artifacts are written to
+    #[arg(short, long, default_value = "output")]
+    pub output_dir: PathBuf,
+
+    /// Fail without writing anything if any asset cannot be loaded or resolved
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Args)]
+pub struct PaletteOptions {
+    /// Palette id, also the name of the palette directory
+    #[arg(long, default_value = "minecraft")]
+    pub palette_id: String,
+
+    /// Human readable palette name
+    #[arg(long, default_value = "Minecraft Palette")]
+    pub palette_name: String,
+
+    /// Only include the default state of each block.
+    /// Needs `--java-src` or `--blocks-report` to know the defaults
+    #[arg(long, requires = "registry")]
+    pub default_states_only: bool,
+
+    /// Also include blocks that are not full cubes, as volumes of N³ voxels
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..=MAX_GRID_SIZE as i64)
+    )]
+    pub volume_resolution: Option<u16>,
+
+    /// Biome temperature the grass and foliage colors of tinted faces are taken at
+    #[arg(long, default_value_t = Climate::PLAINS.temperature)]
+    pub temperature: f32,
+
+    /// Biome downfall the grass and foliage colors of tinted faces are taken at
+    #[arg(long, default_value_t = Climate::PLAINS.downfall)]
+    pub downfall: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Artifact {
+    /// `blocks.json`
+    Blocks,
+    /// `full_blocks.json`
+    FullBlocks,
+    /// `empty_blocks.json`
+    EmptyBlocks,
+    /// `shapes.json`
+    Shapes,
+    /// Palette directory and its textures
+    Palette,
+}
+
+impl Artifact {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Artifact::Blocks,
+            Artifact::FullBlocks,
+            Artifact::EmptyBlocks,
+            Artifact::Shapes,
+            Artifact::Palette,
+        ]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Facing {
+    /// A floor seen from above, with the top of the image north
+    #[value(alias = "floor")]
+    Up,
+    /// A ceiling seen from below, with the top of the image south
+    #[value(alias = "ceiling")]
+    Down,
+    North,
+    /// A wall seen from the south
+    #[value(alias = "wall")]
+    South,
+    East,
+    West,
+}
+
+impl From<Facing> for art::Facing {
+    fn from(facing: Facing) -> Self {
+        match facing {
+            Facing::Up => art::Facing::Up,
+            Facing::Down => art::Facing::Down,
+            Facing::North => art::Facing::North,
+            Facing::South => art::Facing::South,
+            Facing::East => art::Facing::East,
+            Facing::West => art::Facing::West,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Dither {
+    /// Closest block for every pixel
+    None,
+    /// Floyd–Steinberg error diffusion
+    FloydSteinberg,
+    /// 4×4 Bayer matrix
+    Ordered,
+}
+
+impl From<Dither> for art::Dither {
+    fn from(dither: Dither) -> Self {
+        match dither {
+            Dither::None => art::Dither::None,
+            Dither::FloydSteinberg => art::Dither::FloydSteinberg,
+            Dither::Ordered => art::Dither::Ordered,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DeltaE {
+    /// Euclidean distance in OKLab
+    Ok,
+    /// CIE 1976
+    Cie76,
+    /// CIE 1994
+    Cie94,
+    /// CIEDE2000
+    Ciede2000,
+}
+
+impl From<DeltaE> for matching::DeltaE {
+    fn from(delta_e: DeltaE) -> Self {
+        match delta_e {
+            DeltaE::Ok => matching::DeltaE::Ok,
+            DeltaE::Cie76 => matching::DeltaE::Cie76,
+            DeltaE::Cie94 => matching::DeltaE::Cie94,
+            DeltaE::Ciede2000 => matching::DeltaE::Ciede2000,
+        }
+    }
+}
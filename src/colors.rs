@@ -0,0 +1,340 @@
+//! Representative colors of materials, computed from the pixels their faces show.
+//!
+//! Face textures are cropped to their UV region and multiplied by their tint. Animated
+//! materials count the pixels of every frame, and volumes the pixel each voxel face samples.
+
+use std::collections::{BTreeMap, HashMap};
+
+use image::{Rgba, RgbaImage};
+use indexmap::IndexMap;
+
+use crate::{
+    error::{Diagnostics, Error},
+    palette::{
+        BlockTexture, BlockVolume, Color, FaceColors, FaceTexture, Material, MaterialColors,
+        MaterialDisplay, TEXTURES_DIR,
+    },
+    schema::location::ResourceLocation,
+    source::{self, AssetSource},
+    textures::read_image,
+    voxel::VoxelGrid,
+};
+
+/// Clusters the pixels of a material are grouped into to find its dominant color.
+pub const DOMINANT_CLUSTERS: usize = 4;
+
+/// Most rounds of moving the cluster centers before the dominant color is taken.
+const MAX_ITERATIONS: usize = 16;
+
+/// Position of each voxel face, indexed like [`crate::voxel::FACE_NAMES`], in the
+/// `x nx y ny z nz` order of [`FaceColors`], where `z` faces north.
+const VOXEL_FACES: [usize; 6] = [0, 1, 2, 3, 5, 4];
+
+/// Decoded textures, each read from their source once.
+pub struct TextureImages<'a> {
+    source: &'a dyn AssetSource,
+    /// Path of the PNG of a texture in `source`
+    path: fn(&ResourceLocation) -> String,
+    /// Animation frames, which only exist in memory while the palette is built
+    frames: Option<&'a IndexMap<ResourceLocation, RgbaImage>>,
+    images: HashMap<ResourceLocation, RgbaImage>,
+}
+
+impl<'a> TextureImages<'a> {
+    /// Textures of the assets in `source`, with the animation frames in `frames`.
+    pub fn assets(
+        source: &'a dyn AssetSource,
+        frames: &'a IndexMap<ResourceLocation, RgbaImage>,
+    ) -> Self {
+        Self {
+            source,
+            path: |t| t.asset_path("textures", "png"),
+            frames: Some(frames),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Textures of the palette directory `source`, see [`TEXTURES_DIR`].
+    pub fn palette(source: &'a dyn AssetSource) -> Self {
+        Self {
+            source,
+            path: |t| source::join(TEXTURES_DIR, &format!("{}.png", t.file_stem())),
+            frames: None,
+            images: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, texture: &ResourceLocation) -> Result<&RgbaImage, Error> {
+        if let Some(frame) = self.frames.and_then(|f| f.get(texture)) {
+            return Ok(frame);
+        }
+        if !self.images.contains_key(texture) {
+            let image = read_image((self.path)(texture), self.source)?;
+            self.images.insert(texture.clone(), image);
+        }
+        Ok(&self.images[texture])
+    }
+}
+
+/// Computes the colors of the `materials` that have none yet, see [`material_colors`].
+///
+/// Materials whose files cannot be read are reported to `diagnostics` and left without
+/// colors. Returns the number of materials colored.
+pub fn add_colors<'m>(
+    materials: impl Iterator<Item = (&'m String, &'m mut Material)>,
+    images: &mut TextureImages,
+    volume: &mut dyn FnMut(&str) -> Result<VoxelGrid, Error>,
+    diagnostics: &mut Diagnostics,
+) -> usize {
+    let mut colored = 0;
+
+    for (id, material) in materials {
+        if material.colors.is_some() {
+            continue;
+        }
+
+        match material_colors(&material.display, images, volume) {
+            Ok(colors) => {
+                material.colors = Some(colors);
+                colored += 1;
+            }
+            Err(e) => diagnostics.push(Error::MaterialColors {
+                material: id.clone(),
+                source: Box::new(e),
+            }),
+        }
+    }
+
+    colored
+}
+
+/// Colors of a material, with the voxel grids of its volumes read by `volume` from their
+/// [`BlockVolume::path`].
+pub fn material_colors(
+    display: &MaterialDisplay,
+    images: &mut TextureImages,
+    volume: &mut dyn FnMut(&str) -> Result<VoxelGrid, Error>,
+) -> Result<MaterialColors, Error> {
+    let mut volumes = |volumes: &[BlockVolume]| {
+        volumes
+            .iter()
+            .map(|v| {
+                let grid = volume(&v.path)?;
+                let rotated = grid.rotated([v.rotation_x, v.rotation_y, v.rotation_z]);
+                Ok((rotated, v.tint))
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    };
+
+    match display {
+        MaterialDisplay::Texture(texture) => texture_colors(std::slice::from_ref(texture), images),
+        MaterialDisplay::TextureAnimation { frames, .. } => texture_colors(frames, images),
+        MaterialDisplay::Volume(v) => volume_colors(&volumes(std::slice::from_ref(v))?, images),
+        MaterialDisplay::VolumeAnimation { frames, .. } => volume_colors(&volumes(frames)?, images),
+    }
+}
+
+/// Colors of a textured material showing each of `frames` in turn.
+pub fn texture_colors(
+    frames: &[BlockTexture],
+    images: &mut TextureImages,
+) -> Result<MaterialColors, Error> {
+    let mut faces: [Histogram; 6] = Default::default();
+
+    for frame in frames {
+        for (histogram, face) in faces.iter_mut().zip(frame.faces()) {
+            add_face(histogram, face, images.get(&face.path)?);
+        }
+    }
+
+    Ok(colors(faces))
+}
+
+/// Colors of a volume material, from the voxel faces of `grids` placed as the material
+/// places them.
+pub fn volume_colors(
+    grids: &[(VoxelGrid, Option<Color>)],
+    images: &mut TextureImages,
+) -> Result<MaterialColors, Error> {
+    let mut faces: [Histogram; 6] = Default::default();
+
+    for (grid, tint) in grids {
+        for (_, voxel) in grid.voxels() {
+            for (i, sample) in voxel.faces.iter().enumerate() {
+                let Some(sample) = sample else {
+                    continue;
+                };
+                let image = images.get(&grid.textures[sample.texture as usize])?;
+                let (width, height) = image.dimensions();
+                let [x, y] = [(sample.uv[0], width), (sample.uv[1], height)]
+                    .map(|(c, size)| ((c / 16.0 * size as f32) as u32).min(size - 1));
+
+                let tint = tint.filter(|_| sample.tinted);
+                faces[VOXEL_FACES[i]].add(*image.get_pixel(x, y), tint);
+            }
+        }
+    }
+
+    Ok(colors(faces))
+}
+
+fn colors(faces: [Histogram; 6]) -> MaterialColors {
+    let mut all = Histogram::default();
+    for face in &faces {
+        all.extend(face);
+    }
+
+    let [x, nx, y, ny, z, nz] = faces.map(|f| f.average());
+    MaterialColors {
+        average: all.average(),
+        dominant: all.dominant(),
+        faces: FaceColors {
+            x,
+            nx,
+            y,
+            ny,
+            z,
+            nz,
+        },
+    }
+}
+
+/// Adds the pixels of the UV region of `face` to `histogram`.
+fn add_face(histogram: &mut Histogram, face: &FaceTexture, image: &RgbaImage) {
+    let (columns, rows) = face.uv_pixels(image.width(), image.height());
+    for y in rows {
+        for x in columns.clone() {
+            histogram.add(*image.get_pixel(x, y), face.tint);
+        }
+    }
+}
+
+/// Total alpha of each color the pixels of a material have.
+#[derive(Default)]
+struct Histogram {
+    weights: BTreeMap<[u8; 3], f64>,
+    pixels: usize,
+}
+
+impl Histogram {
+    fn add(&mut self, pixel: Rgba<u8>, tint: Option<Color>) {
+        let [r, g, b, a] = pixel.0;
+        let mut color = [r, g, b];
+        if let Some(tint) = tint {
+            for (c, t) in color.iter_mut().zip([tint.r, tint.g, tint.b]) {
+                *c = (*c as u16 * t as u16 / 255) as u8;
+            }
+        }
+
+        *self.weights.entry(color).or_default() += a as f64 / 255.0;
+        self.pixels += 1;
+    }
+
+    fn extend(&mut self, other: &Histogram) {
+        for (color, weight) in &other.weights {
+            *self.weights.entry(*color).or_default() += weight;
+        }
+        self.pixels += other.pixels;
+    }
+
+    fn total(&self) -> f64 {
+        self.weights.values().sum()
+    }
+
+    fn average(&self) -> Color {
+        let total = self.total();
+        if total == 0.0 {
+            return to_color([0.0; 3], 0.0);
+        }
+
+        let mut sum = [0.0; 3];
+        for (color, weight) in &self.weights {
+            for (s, c) in sum.iter_mut().zip(color) {
+                *s += *c as f64 * weight;
+            }
+        }
+        to_color(sum.map(|s| s / total), total / self.pixels as f64)
+    }
+
+    /// Center of the heaviest of [`DOMINANT_CLUSTERS`] clusters found by weighted k-means.
+    ///
+    /// Clusters start at the average and then at the pixels farthest from every start so
+    /// far, which keeps the result deterministic.
+    fn dominant(&self) -> Color {
+        let points: Vec<([f64; 3], f64)> = self
+            .weights
+            .iter()
+            .filter(|(_, w)| **w > 0.0)
+            .map(|(c, w)| (c.map(f64::from), *w))
+            .collect();
+        let Some(first) = points.first() else {
+            return to_color([0.0; 3], 0.0);
+        };
+
+        let average = self.average();
+        let mut centers = vec![[average.r, average.g, average.b].map(f64::from)];
+        while centers.len() < DOMINANT_CLUSTERS {
+            let (farthest, d) = points
+                .iter()
+                .map(|(p, _)| (*p, nearest(&centers, p).1))
+                .fold(
+                    (first.0, 0.0),
+                    |best, p| if p.1 > best.1 { p } else { best },
+                );
+            if d == 0.0 {
+                break;
+            }
+            centers.push(farthest);
+        }
+
+        let mut weights = vec![0.0; centers.len()];
+        for _ in 0..MAX_ITERATIONS {
+            let mut sums = vec![[0.0; 3]; centers.len()];
+            weights = vec![0.0; centers.len()];
+            for (p, w) in &points {
+                let i = nearest(&centers, p).0;
+                for (s, c) in sums[i].iter_mut().zip(p) {
+                    *s += c * w;
+                }
+                weights[i] += w;
+            }
+
+            let moved: Vec<[f64; 3]> = centers
+                .iter()
+                .zip(sums.iter().zip(&weights))
+                .map(|(c, (s, w))| if *w > 0.0 { s.map(|s| s / w) } else { *c })
+                .collect();
+            if moved == centers {
+                break;
+            }
+            centers = moved;
+        }
+
+        let heaviest = (0..centers.len())
+            .max_by(|&a, &b| weights[a].total_cmp(&weights[b]))
+            .unwrap_or(0);
+        to_color(centers[heaviest], 1.0)
+    }
+}
+
+/// Index of and squared distance to the center closest to `p`.
+fn nearest(centers: &[[f64; 3]], p: &[f64; 3]) -> (usize, f64) {
+    centers
+        .iter()
+        .map(|c| (0..3).map(|a| (c[a] - p[a]).powi(2)).sum::<f64>())
+        .enumerate()
+        .fold(
+            (0, f64::INFINITY),
+            |best, d| if d.1 < best.1 { d } else { best },
+        )
+}
+
+fn to_color([r, g, b]: [f64; 3], alpha: f64) -> Color {
+    let channel = |c: f64| c.round().clamp(0.0, 255.0) as u8;
+    Color {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+        a: channel(alpha * 255.0),
+    }
+}
@@ -1,14 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::schema::{
-    blockstate::{BlockState, ModelVariant},
-    model::Model,
+use crate::{
+    baked::BakedModel,
+    error::{Diagnostics, Error},
+    schema::{
+        blockstate::{BlockState, ModelVariant},
+        location::ResourceLocation,
+    },
 };
 
 pub fn get_all_empty_blocks(
-    blockstates: &HashMap<String, BlockState>,
-    models: &HashMap<String, Model>,
-) -> HashSet<String> {
+    blockstates: &HashMap<ResourceLocation, BlockState>,
+    models: &HashMap<ResourceLocation, BakedModel>,
+    diagnostics: &mut Diagnostics,
+) -> HashSet<ResourceLocation> {
     let mut empty_blocks = HashSet::new();
 
     for (block_name, blockstate) in blockstates {
@@ -21,10 +26,16 @@ pub fn get_all_empty_blocks(
             continue;
         }
 
-        let model = models
-            .get(&v.models()[0].model)
-            .expect("Model should exist");
-        if model.elements.is_empty() && model.parent.is_none() && model.textures.is_empty() {
+        let model_name = &v.models()[0].model;
+        let Some(model) = models.get(model_name) else {
+            diagnostics.push(Error::MissingModel {
+                model: model_name.clone(),
+                referenced_by: block_name.to_string(),
+            });
+            continue;
+        };
+
+        if model.elements.is_empty() && model.textures.is_empty() {
             empty_blocks.insert(block_name.to_owned());
         }
     }
@@ -33,9 +44,10 @@ pub fn get_all_empty_blocks(
 }
 
 pub fn get_all_full_cube_blocks(
-    blockstates: &HashMap<String, BlockState>,
-    models: &HashMap<String, Model>,
-) -> HashSet<String> {
+    blockstates: &HashMap<ResourceLocation, BlockState>,
+    models: &HashMap<ResourceLocation, BakedModel>,
+    diagnostics: &mut Diagnostics,
+) -> HashSet<ResourceLocation> {
     let mut full_cube_blocks = HashSet::new();
 
     for (block_name, blockstate) in blockstates {
@@ -43,7 +55,7 @@ pub fn get_all_full_cube_blocks(
 
         if !blockstate.variants.is_empty() {
             for value in blockstate.variants.values() {
-                if !is_variant_full_cube(value, models) {
+                if !is_variant_full_cube(block_name, value, models, diagnostics) {
                     all_models_full_cube = false;
                     break;
                 }
@@ -60,30 +72,31 @@ pub fn get_all_full_cube_blocks(
     full_cube_blocks
 }
 
-fn is_variant_full_cube(variant: &ModelVariant, models: &HashMap<String, Model>) -> bool {
-    match variant {
-        ModelVariant::Single(v) => is_model_full_cube(&v.model, models),
-        ModelVariant::Multiple(vs) => vs.iter().all(|v| is_model_full_cube(&v.model, models)),
-    }
+fn is_variant_full_cube(
+    block_name: &ResourceLocation,
+    variant: &ModelVariant,
+    models: &HashMap<ResourceLocation, BakedModel>,
+    diagnostics: &mut Diagnostics,
+) -> bool {
+    variant
+        .models()
+        .iter()
+        .all(|v| is_model_full_cube(&v.model, &block_name.to_string(), models, diagnostics))
 }
 
-fn is_model_full_cube(model_name: &str, models: &HashMap<String, Model>) -> bool {
-    let model = models.get(model_name).expect("Model should exist");
-
-    let is_full_cube = !model.elements.is_empty()
-        && model
-            .elements
-            .iter()
-            .all(|e| e.from == [0.0, 0.0, 0.0] && e.to == [16.0, 16.0, 16.0]);
-
-    if is_full_cube {
-        return true;
-    }
-
-    if let Some(parent) = &model.parent {
-        let parent_is_full = is_model_full_cube(parent, models);
-        return parent_is_full;
-    }
-
-    false
+fn is_model_full_cube(
+    model_name: &ResourceLocation,
+    referenced_by: &str,
+    models: &HashMap<ResourceLocation, BakedModel>,
+    diagnostics: &mut Diagnostics,
+) -> bool {
+    let Some(model) = models.get(model_name) else {
+        diagnostics.push(Error::MissingModel {
+            model: model_name.clone(),
+            referenced_by: referenced_by.to_owned(),
+        });
+        return false;
+    };
+
+    model.is_full_cube()
 }
@@ -0,0 +1,286 @@
+use std::{collections::HashSet, fmt, io};
+
+use crate::schema::location::ResourceLocation;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: String,
+        source: io::Error,
+    },
+    Json {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// `model` is referenced by a blockstate or as a parent but was not loaded.
+    MissingModel {
+        model: ResourceLocation,
+        referenced_by: String,
+    },
+    /// No blockstate file was loaded for `block`.
+    MissingBlockstate {
+        block: ResourceLocation,
+    },
+    /// The blockstate of `block` has no variant with `key`.
+    MissingVariant {
+        block: ResourceLocation,
+        key: String,
+    },
+    UnresolvedTexture {
+        model: ResourceLocation,
+        variable: String,
+    },
+    /// Models that are each other's parents, in parent order with the first one repeated.
+    ParentCycle {
+        chain: Vec<ResourceLocation>,
+    },
+    /// The parent chain of `model` is longer than `max_depth`.
+    ParentChainTooDeep {
+        model: ResourceLocation,
+        max_depth: usize,
+    },
+    /// Texture variables of `model` that refer to each other, with the first one repeated.
+    TextureCycle {
+        model: ResourceLocation,
+        chain: Vec<String>,
+    },
+    /// `model` was expected to have a full cube element but has none.
+    NotCube {
+        model: ResourceLocation,
+    },
+    /// A face of `model` turns its texture by an angle that is not a multiple of 90.
+    InvalidFaceRotation {
+        model: ResourceLocation,
+        face: String,
+        rotation: i32,
+    },
+    /// A blockstate variant of `referenced_by` rotates `model` by an angle that is not a
+    /// multiple of 90.
+    InvalidRotation {
+        model: ResourceLocation,
+        referenced_by: String,
+        degrees: i32,
+    },
+    /// A blockstate variant of `referenced_by` gives `model` a weight that is not positive.
+    InvalidWeight {
+        model: ResourceLocation,
+        referenced_by: String,
+        weight: i32,
+    },
+    /// An element of `model` is rotated by more than the 45 degrees the game supports.
+    InvalidElementRotation {
+        model: ResourceLocation,
+        angle: f32,
+    },
+    /// A volume material refers to a voxel grid that does not exist.
+    MissingVolume {
+        path: String,
+    },
+    /// An image under `path` could not be decoded.
+    Image {
+        path: String,
+        message: String,
+    },
+    /// The `.mcmeta` animation of `texture` does not fit its image.
+    InvalidAnimation {
+        texture: ResourceLocation,
+        message: String,
+    },
+    /// Animated `textures` shown together by a block take more than `max_frames` frames, so
+    /// the block shows the first frame only.
+    TooManyAnimationFrames {
+        textures: Vec<ResourceLocation>,
+        max_frames: u64,
+    },
+    /// The colors of `material` could not be computed.
+    MaterialColors {
+        material: String,
+        source: Box<Error>,
+    },
+    /// A block class of the game sources uses a property whose definition was not found.
+    UnresolvedProperty {
+        class: String,
+        property: String,
+    },
+}
+
+impl Error {
+    pub fn json(path: &str, e: serde_json::Error) -> Self {
+        let (line, column) = (e.line(), e.column());
+        let message = e.to_string();
+        let position = format!(" at line {} column {}", line, column);
+
+        Error::Json {
+            path: path.to_owned(),
+            line,
+            column,
+            message: message
+                .strip_suffix(&position)
+                .unwrap_or(&message)
+                .to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "Failed to read {}: {}", path, source),
+            Error::Json {
+                path,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Failed to parse {} at line {} column {}: {}",
+                path, line, column, message
+            ),
+            Error::MissingModel {
+                model,
+                referenced_by,
+            } => write!(
+                f,
+                "Model {} referenced by {} not found",
+                model, referenced_by
+            ),
+            Error::MissingBlockstate { block } => {
+                write!(f, "Blockstate of block {} not found", block)
+            }
+            Error::MissingVariant { block, key } => {
+                write!(
+                    f,
+                    "Blockstate of block {} has no variant \"{}\"",
+                    block, key
+                )
+            }
+            Error::UnresolvedTexture { model, variable } => write!(
+                f,
+                "Texture variable #{} of model {} cannot be resolved",
+                variable, model
+            ),
+            Error::ParentCycle { chain } => {
+                let chain: Vec<String> = chain.iter().map(|m| m.to_string()).collect();
+                write!(f, "Model parents form a cycle: {}", chain.join(" -> "))
+            }
+            Error::ParentChainTooDeep { model, max_depth } => write!(
+                f,
+                "Parent chain of model {} is deeper than {} models",
+                model, max_depth
+            ),
+            Error::TextureCycle { model, chain } => {
+                let chain: Vec<String> = chain.iter().map(|v| format!("#{}", v)).collect();
+                write!(
+                    f,
+                    "Texture variables of model {} form a cycle: {}",
+                    model,
+                    chain.join(" -> ")
+                )
+            }
+            Error::NotCube { model } => {
+                write!(f, "Model {} has no full cube element", model)
+            }
+            Error::InvalidFaceRotation {
+                model,
+                face,
+                rotation,
+            } => write!(
+                f,
+                "Face {} of model {} has invalid rotation {}",
+                face, model, rotation
+            ),
+            Error::InvalidRotation {
+                model,
+                referenced_by,
+                degrees,
+            } => write!(
+                f,
+                "Model {} is rotated by {} degrees in {}, which is not a multiple of 90",
+                model, degrees, referenced_by
+            ),
+            Error::InvalidWeight {
+                model,
+                referenced_by,
+                weight,
+            } => write!(
+                f,
+                "Model {} has weight {} in {}, which is not positive; using 1 instead",
+                model, weight, referenced_by
+            ),
+            Error::InvalidElementRotation { model, angle } => write!(
+                f,
+                "Model {} has an element rotated by {} degrees, more than 45",
+                model, angle
+            ),
+            Error::MissingVolume { path } => write!(f, "Volume {} not found", path),
+            Error::Image { path, message } => {
+                write!(f, "Failed to decode image {}: {}", path, message)
+            }
+            Error::MaterialColors { material, source } => {
+                write!(f, "Failed to compute colors of {}: {}", material, source)
+            }
+            Error::InvalidAnimation { texture, message } => {
+                write!(f, "Invalid animation of texture {}: {}", texture, message)
+            }
+            Error::TooManyAnimationFrames {
+                textures,
+                max_frames,
+            } => write!(
+                f,
+                "Animations of {} together take more than {} frames; showing the first frame",
+                textures
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                max_frames
+            ),
+            Error::UnresolvedProperty { class, property } => write!(
+                f,
+                "Property {} of block class {} cannot be resolved",
+                property, class
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::MaterialColors { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Problems collected over a whole run instead of aborting on the first one.
+///
+/// Identical problems reported from several places are only kept once.
+#[derive(Default)]
+pub struct Diagnostics {
+    errors: Vec<Error>,
+    seen: HashSet<String>,
+}
+
+impl Diagnostics {
+    pub fn push(&mut self, error: Error) {
+        if self.seen.insert(error.to_string()) {
+            self.errors.push(error);
+        }
+    }
+
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+}
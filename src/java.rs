@@ -0,0 +1,972 @@
+//! Block properties read from decompiled game sources with Mojang mappings.
+//!
+//! `Blocks.java` gives the id and class of every block. Each block class and its
+//! superclasses declare the properties in `createBlockStateDefinition` and the default
+//! state in `registerDefaultState`. The properties themselves are created in
+//! `BlockStateProperties.java` and their values come from enums like `Direction`.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use tree_sitter::{Node, Parser};
+
+use crate::{
+    error::{Diagnostics, Error},
+    registry::{BlockDefinition, BlockRegistry},
+    schema::location::ResourceLocation,
+};
+
+/// Superclass chains and field references deeper than this are treated as unresolvable.
+const MAX_DEPTH: usize = 32;
+
+/// Reads the properties and default state of every block registered in `Blocks.java`.
+///
+/// `src_root` is searched recursively for `.java` files, which are looked up by class name.
+/// Properties that cannot be resolved are reported to `diagnostics`, and their blocks are
+/// left out so their states are inferred from the blockstate files instead.
+pub fn load_block_registry<P: AsRef<Path>>(
+    src_root: P,
+    diagnostics: &mut Diagnostics,
+) -> io::Result<BlockRegistry> {
+    let mut sources = Sources::new(src_root.as_ref())?;
+
+    let blocks_path = sources.files.get("Blocks").cloned().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Blocks.java not found in {}", src_root.as_ref().display()),
+        )
+    })?;
+    let src = read_source(&blocks_path)?;
+    let tree = sources.parse(&src);
+
+    let mut registry = BlockRegistry::default();
+
+    for (id, class) in registered_blocks(tree.root_node(), &src, &mut sources) {
+        let Some(properties) = sources.block_properties(&class, 0, diagnostics) else {
+            continue;
+        };
+        let defaults = sources.block_defaults(&class, 0, diagnostics);
+
+        let mut definition = BlockDefinition::default();
+        for property in properties {
+            let default = defaults
+                .get(&property.name)
+                .filter(|v| property.values.contains(v))
+                .unwrap_or(&property.values[0]);
+
+            definition
+                .default_state
+                .insert(property.name.clone(), default.clone());
+            definition.properties.insert(property.name, property.values);
+        }
+
+        registry
+            .blocks
+            .insert(ResourceLocation::minecraft(id), definition);
+    }
+
+    Ok(registry)
+}
+
+/// A resolved block state property.
+#[derive(Clone, Debug)]
+struct Property {
+    name: String,
+    /// Serialized values in the order the game enumerates them
+    values: Vec<String>,
+    /// Java constant names of `values`, for enum properties only
+    constants: Vec<String>,
+}
+
+/// What is known about a parsed class or enum.
+#[derive(Default)]
+struct ClassInfo {
+    superclass: Option<String>,
+    /// Static fields that create or refer to a property, or hold an `int` constant
+    fields: HashMap<String, FieldInit>,
+    /// Methods without parameters that return a constant, like `getMaxSegmentIndex`
+    constant_methods: HashMap<String, FieldInit>,
+    state_definition: Option<StateDefinition>,
+    default_state: Option<DefaultState>,
+    enum_constants: Vec<EnumConstant>,
+}
+
+enum FieldInit {
+    /// Another field, possibly qualified like `BlockStateProperties.AXIS`
+    Ref(String),
+    Property(PropertyInit),
+    Int(i64),
+    /// A method called without arguments, possibly qualified like
+    /// `RotationSegment.getMaxSegmentIndex`
+    Call(String),
+}
+
+/// Arguments of a `*Property.create(...)` call.
+struct PropertyInit {
+    kind: String,
+    name: String,
+    /// Minimum and maximum of an `IntegerProperty`, as literals or constant references
+    bounds: Vec<String>,
+    enum_class: Option<String>,
+    filter: EnumFilter,
+}
+
+enum EnumFilter {
+    All,
+    Only(Vec<String>),
+    Except(Vec<String>),
+    /// A field such as `Direction.Plane.HORIZONTAL` listing the allowed constants
+    Field(String),
+}
+
+/// Properties added in `createBlockStateDefinition`.
+struct StateDefinition {
+    added: Vec<String>,
+    calls_super: bool,
+}
+
+/// The `setValue` chain passed to `registerDefaultState`.
+struct DefaultState {
+    /// Whether the chain starts from the inherited `defaultBlockState()`
+    inherits: bool,
+    values: Vec<(String, Value)>,
+}
+
+enum Value {
+    Literal(String),
+    Constant(String),
+}
+
+struct EnumConstant {
+    name: String,
+    serialized: String,
+    /// Constant lists passed as arguments, like the faces of a `Direction.Plane`
+    arrays: Vec<Vec<String>>,
+}
+
+struct Sources {
+    files: HashMap<String, PathBuf>,
+    classes: HashMap<String, Rc<ClassInfo>>,
+    parsed: HashSet<String>,
+    parser: Parser,
+}
+
+impl Sources {
+    fn new(root: &Path) -> io::Result<Self> {
+        let mut files = HashMap::new();
+        index_sources(root, &mut files)?;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .expect("tree-sitter-java grammar is incompatible with tree-sitter");
+
+        Ok(Self {
+            files,
+            classes: HashMap::new(),
+            parsed: HashSet::new(),
+            parser,
+        })
+    }
+
+    fn parse(&mut self, src: &str) -> tree_sitter::Tree {
+        self.parser
+            .parse(src, None)
+            .expect("parsing without a timeout or cancellation flag always succeeds")
+    }
+
+    /// Looks up a class by simple name, or by `Outer.Inner` for nested types.
+    ///
+    /// Classes without a source file, like those from libraries, are `None`.
+    fn class(&mut self, name: &str) -> Option<Rc<ClassInfo>> {
+        let name = name.split('<').next().unwrap_or(name).trim();
+
+        if let Some(info) = self.classes.get(name) {
+            return Some(info.clone());
+        }
+
+        let outer = name.split('.').next().unwrap_or(name);
+        if !self.parsed.insert(outer.to_owned()) {
+            return None;
+        }
+
+        let path = self.files.get(outer)?.clone();
+        let src = read_source(&path).ok()?;
+        let tree = self.parse(&src);
+
+        let mut classes = HashMap::new();
+        collect_types(tree.root_node(), &src, "", &mut classes);
+        self.classes
+            .extend(classes.into_iter().map(|(k, v)| (k, Rc::new(v))));
+
+        self.classes.get(name).cloned()
+    }
+
+    fn is_block_class(&mut self, name: &str) -> bool {
+        let mut current = name.to_owned();
+
+        for _ in 0..MAX_DEPTH {
+            if current == "Block" {
+                return true;
+            }
+            match self.class(&current).and_then(|c| c.superclass.clone()) {
+                Some(superclass) => current = superclass,
+                None => return false,
+            }
+        }
+
+        false
+    }
+
+    /// Finds the class declaring `field`, searching `class` and then its superclasses.
+    fn field_owner(&mut self, class: &str, field: &str) -> Option<(String, Rc<ClassInfo>)> {
+        let mut current = class.to_owned();
+
+        for _ in 0..MAX_DEPTH {
+            let info = self.class(&current)?;
+            if info.fields.contains_key(field) {
+                return Some((current, info));
+            }
+            current = info.superclass.clone()?;
+        }
+
+        None
+    }
+
+    /// Resolves a property reference such as `AXIS` or `BlockStateProperties.AXIS` made in `class`.
+    fn property(&mut self, class: &str, reference: &str, depth: usize) -> Option<Property> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let (owner, field) = match reference.rsplit_once('.') {
+            Some((owner, field)) => (owner, field),
+            None => (class, reference),
+        };
+        let (owner, info) = self.field_owner(owner, field)?;
+
+        match &info.fields[field] {
+            FieldInit::Ref(r) => self.property(&owner, r, depth + 1),
+            FieldInit::Property(init) => self.create_property(&owner, init),
+            FieldInit::Int(_) | FieldInit::Call(_) => None,
+        }
+    }
+
+    /// Resolves an `int` literal or a reference to an `int` constant made in `class`, such
+    /// as `MAX_ROTATIONS_16` set to `RotationSegment.getMaxSegmentIndex()`.
+    fn int_constant(&mut self, class: &str, reference: &str, depth: usize) -> Option<i64> {
+        if let Ok(value) = reference.parse() {
+            return Some(value);
+        }
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let (owner, field) = match reference.rsplit_once('.') {
+            Some((owner, field)) => (owner, field),
+            None => (class, reference),
+        };
+        let (owner, info) = self.field_owner(owner, field)?;
+
+        self.resolve_int(&owner, &info.fields[field], depth)
+    }
+
+    fn resolve_int(&mut self, class: &str, init: &FieldInit, depth: usize) -> Option<i64> {
+        match init {
+            FieldInit::Int(value) => Some(*value),
+            FieldInit::Ref(r) => self.int_constant(class, r, depth + 1),
+            FieldInit::Call(method) => {
+                let (owner, method) = match method.rsplit_once('.') {
+                    Some((owner, method)) => (owner, method),
+                    None => (class, method.as_str()),
+                };
+                let info = self.class(owner)?;
+                let body = info.constant_methods.get(method)?;
+                self.resolve_int(owner, body, depth + 1)
+            }
+            FieldInit::Property(_) => None,
+        }
+    }
+
+    /// Values of the property `init` creates, with int constants resolved from `class`.
+    fn create_property(&mut self, class: &str, init: &PropertyInit) -> Option<Property> {
+        let (values, constants) = match init.kind.as_str() {
+            "BooleanProperty" => (vec!["true".to_owned(), "false".to_owned()], Vec::new()),
+            "IntegerProperty" => {
+                let [min, max] = &init.bounds[..] else {
+                    return None;
+                };
+                let min = self.int_constant(class, min, 0)?;
+                let max = self.int_constant(class, max, 0)?;
+                ((min..=max).map(|i| i.to_string()).collect(), Vec::new())
+            }
+            _ => {
+                let enum_class = init.enum_class.as_deref()?;
+                let info = self.class(enum_class)?;
+
+                let selected: Vec<&EnumConstant> = match &init.filter {
+                    EnumFilter::All => info.enum_constants.iter().collect(),
+                    EnumFilter::Only(names) => names
+                        .iter()
+                        .filter_map(|n| info.enum_constants.iter().find(|c| &c.name == n))
+                        .collect(),
+                    EnumFilter::Except(names) => info
+                        .enum_constants
+                        .iter()
+                        .filter(|c| !names.contains(&c.name))
+                        .collect(),
+                    EnumFilter::Field(field) => {
+                        let names = self.enum_field_constants(enum_class, field)?;
+                        names
+                            .iter()
+                            .filter_map(|n| info.enum_constants.iter().find(|c| &c.name == n))
+                            .collect()
+                    }
+                };
+
+                (
+                    selected.iter().map(|c| c.serialized.clone()).collect(),
+                    selected.iter().map(|c| c.name.clone()).collect(),
+                )
+            }
+        };
+
+        if values.is_empty() {
+            return None;
+        }
+
+        Some(Property {
+            name: init.name.clone(),
+            values,
+            constants,
+        })
+    }
+
+    /// Constants of `enum_class` named by `field`.
+    ///
+    /// `Direction.NORTH` names a single constant, while `Direction.Plane.HORIZONTAL` is
+    /// a constant of another enum whose first array argument lists the constants.
+    fn enum_field_constants(&mut self, enum_class: &str, field: &str) -> Option<Vec<String>> {
+        let (owner, name) = field.rsplit_once('.').unwrap_or(("", field));
+        let simple_class = enum_class.rsplit('.').next().unwrap_or(enum_class);
+
+        if owner.is_empty() || owner == enum_class || owner == simple_class {
+            return Some(vec![name.to_owned()]);
+        }
+
+        let info = self.class(owner)?;
+        let constant = info.enum_constants.iter().find(|c| c.name == name)?;
+        constant.arrays.first().cloned()
+    }
+
+    /// Properties of a block class in the order they are added, `None` if any of them
+    /// cannot be resolved.
+    fn block_properties(
+        &mut self,
+        class: &str,
+        depth: usize,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<Vec<Property>> {
+        if depth > MAX_DEPTH {
+            return Some(Vec::new());
+        }
+        let Some(info) = self.class(class) else {
+            return Some(Vec::new());
+        };
+
+        let Some(definition) = &info.state_definition else {
+            return match &info.superclass {
+                Some(superclass) => self.block_properties(superclass, depth + 1, diagnostics),
+                None => Some(Vec::new()),
+            };
+        };
+
+        let inherited = match (&info.superclass, definition.calls_super) {
+            (Some(superclass), true) => self.block_properties(superclass, depth + 1, diagnostics),
+            _ => Some(Vec::new()),
+        };
+        let mut resolved = inherited.is_some();
+        let mut properties = inherited.unwrap_or_default();
+
+        for reference in &definition.added {
+            match self.property(class, reference, 0) {
+                Some(p) => {
+                    properties.retain(|q| q.name != p.name);
+                    properties.push(p);
+                }
+                None => {
+                    resolved = false;
+                    diagnostics.push(Error::UnresolvedProperty {
+                        class: class.to_owned(),
+                        property: reference.clone(),
+                    });
+                }
+            }
+        }
+
+        resolved.then_some(properties)
+    }
+
+    /// Property values set by `registerDefaultState` in a block class or its superclasses.
+    fn block_defaults(
+        &mut self,
+        class: &str,
+        depth: usize,
+        diagnostics: &mut Diagnostics,
+    ) -> BTreeMap<String, String> {
+        if depth > MAX_DEPTH {
+            return BTreeMap::new();
+        }
+        let Some(info) = self.class(class) else {
+            return BTreeMap::new();
+        };
+
+        let inherited = |sources: &mut Self, diagnostics: &mut Diagnostics| match &info.superclass {
+            Some(superclass) => sources.block_defaults(superclass, depth + 1, diagnostics),
+            None => BTreeMap::new(),
+        };
+
+        let Some(default_state) = &info.default_state else {
+            return inherited(self, diagnostics);
+        };
+
+        let mut defaults = if default_state.inherits {
+            inherited(self, diagnostics)
+        } else {
+            BTreeMap::new()
+        };
+
+        for (reference, value) in &default_state.values {
+            let Some(property) = self.property(class, reference, 0) else {
+                diagnostics.push(Error::UnresolvedProperty {
+                    class: class.to_owned(),
+                    property: reference.clone(),
+                });
+                continue;
+            };
+
+            let value = match value {
+                Value::Literal(v) => Some(v.clone()),
+                Value::Constant(c) => property
+                    .constants
+                    .iter()
+                    .position(|k| k == c)
+                    .map(|i| property.values[i].clone()),
+            };
+
+            if let Some(value) = value {
+                defaults.insert(property.name, value);
+            }
+        }
+
+        defaults
+    }
+}
+
+fn index_sources(dir: &Path, files: &mut HashMap<String, PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            index_sources(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "java")
+            && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+        {
+            files.entry(stem.to_owned()).or_insert(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_source(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+fn text<'a>(node: Node, src: &'a str) -> &'a str {
+    node.utf8_text(src.as_bytes()).unwrap_or_default()
+}
+
+fn named_children<'t>(node: Node<'t>) -> Vec<Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|n| !n.is_extra())
+        .collect()
+}
+
+/// Pre-order traversal, stopping at the first node `f` returns a value for.
+fn find_map<'t, T>(node: Node<'t>, f: &mut impl FnMut(Node<'t>) -> Option<T>) -> Option<T> {
+    if let Some(found) = f(node) {
+        return Some(found);
+    }
+    named_children(node)
+        .into_iter()
+        .find_map(|child| find_map(child, f))
+}
+
+fn for_each<'t>(node: Node<'t>, f: &mut impl FnMut(Node<'t>)) {
+    f(node);
+    for child in named_children(node) {
+        for_each(child, f);
+    }
+}
+
+/// Strips casts and parentheses around an expression.
+fn unwrap_expression(node: Node) -> Node {
+    let inner = match node.kind() {
+        "cast_expression" => node.child_by_field_name("value"),
+        "parenthesized_expression" => named_children(node).into_iter().next(),
+        _ => None,
+    };
+
+    inner.map_or(node, unwrap_expression)
+}
+
+fn arguments<'t>(invocation: Node<'t>) -> Vec<Node<'t>> {
+    invocation
+        .child_by_field_name("arguments")
+        .map(named_children)
+        .unwrap_or_default()
+}
+
+fn method_name<'a>(invocation: Node, src: &'a str) -> &'a str {
+    invocation
+        .child_by_field_name("name")
+        .map(|n| text(n, src))
+        .unwrap_or_default()
+}
+
+fn string_value(node: Node, src: &str) -> String {
+    text(node, src).trim_matches('"').to_owned()
+}
+
+/// Last segment of a possibly qualified name, e.g. `NORTH` for `Direction.NORTH`.
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Records every class and enum declared under `node`, nested ones as `Outer.Inner`.
+fn collect_types(node: Node, src: &str, prefix: &str, out: &mut HashMap<String, ClassInfo>) {
+    for child in named_children(node) {
+        if !matches!(child.kind(), "class_declaration" | "enum_declaration") {
+            continue;
+        }
+        let (Some(name), Some(body)) = (
+            child.child_by_field_name("name"),
+            child.child_by_field_name("body"),
+        ) else {
+            continue;
+        };
+
+        let name = format!("{}{}", prefix, text(name, src));
+        let mut info = ClassInfo {
+            superclass: child
+                .child_by_field_name("superclass")
+                .and_then(|s| named_children(s).into_iter().next())
+                .map(|t| {
+                    text(t, src)
+                        .split('<')
+                        .next()
+                        .unwrap_or_default()
+                        .to_owned()
+                }),
+            ..Default::default()
+        };
+
+        let mut members = named_children(body);
+        if let Some(declarations) = members
+            .iter()
+            .find(|m| m.kind() == "enum_body_declarations")
+        {
+            members.extend(named_children(*declarations));
+        }
+
+        for member in &members {
+            match member.kind() {
+                "enum_constant" => info.enum_constants.push(enum_constant(*member, src)),
+                "field_declaration" => collect_fields(*member, src, &mut info.fields),
+                "method_declaration"
+                    if method_name(*member, src) == "createBlockStateDefinition" =>
+                {
+                    info.state_definition = Some(state_definition(*member, src));
+                }
+                "method_declaration" => {
+                    if let Some(init) = constant_method(*member, src) {
+                        info.constant_methods
+                            .insert(method_name(*member, src).to_owned(), init);
+                    }
+                }
+                "constructor_declaration" => {
+                    if let Some(d) = default_state(*member, src) {
+                        info.default_state = Some(d);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        collect_types(body, src, &format!("{}.", name), out);
+        if let Some(declarations) = members
+            .iter()
+            .find(|m| m.kind() == "enum_body_declarations")
+        {
+            collect_types(*declarations, src, &format!("{}.", name), out);
+        }
+
+        out.insert(name, info);
+    }
+}
+
+fn enum_constant(node: Node, src: &str) -> EnumConstant {
+    let name = node
+        .child_by_field_name("name")
+        .map(|n| text(n, src).to_owned())
+        .unwrap_or_default();
+    let args = arguments(node);
+
+    let serialized = args
+        .iter()
+        .find(|a| a.kind() == "string_literal")
+        .map(|a| string_value(*a, src))
+        .unwrap_or_else(|| name.to_lowercase());
+
+    let arrays = args
+        .iter()
+        .filter_map(|a| a.child_by_field_name("value"))
+        .filter(|v| v.kind() == "array_initializer")
+        .map(|v| {
+            named_children(v)
+                .into_iter()
+                .map(|c| simple_name(text(c, src)).to_owned())
+                .collect()
+        })
+        .collect();
+
+    EnumConstant {
+        name,
+        serialized,
+        arrays,
+    }
+}
+
+fn collect_fields(node: Node, src: &str, fields: &mut HashMap<String, FieldInit>) {
+    for declarator in named_children(node) {
+        if declarator.kind() != "variable_declarator" {
+            continue;
+        }
+        let (Some(name), Some(value)) = (
+            declarator.child_by_field_name("name"),
+            declarator.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+
+        let Some(init) = field_init(unwrap_expression(value), src) else {
+            continue;
+        };
+        fields.insert(text(name, src).to_owned(), init);
+    }
+}
+
+fn field_init(value: Node, src: &str) -> Option<FieldInit> {
+    match value.kind() {
+        "identifier" | "field_access" => Some(FieldInit::Ref(text(value, src).to_owned())),
+        "decimal_integer_literal" => text(value, src).parse().ok().map(FieldInit::Int),
+        "method_invocation" if arguments(value).is_empty() => {
+            let method = method_name(value, src);
+            Some(FieldInit::Call(match value.child_by_field_name("object") {
+                Some(object) => format!("{}.{}", text(object, src), method),
+                None => method.to_owned(),
+            }))
+        }
+        "method_invocation" => property_init(value, src).map(FieldInit::Property),
+        _ => None,
+    }
+}
+
+/// What a method without parameters whose body is a single `return` returns.
+fn constant_method(method: Node, src: &str) -> Option<FieldInit> {
+    let parameters = method.child_by_field_name("parameters")?;
+    let body = method.child_by_field_name("body")?;
+    let [statement] = named_children(body)[..] else {
+        return None;
+    };
+    if !named_children(parameters).is_empty() || statement.kind() != "return_statement" {
+        return None;
+    }
+
+    field_init(unwrap_expression(*named_children(statement).first()?), src)
+}
+
+fn property_init(invocation: Node, src: &str) -> Option<PropertyInit> {
+    let kind = simple_name(text(invocation.child_by_field_name("object")?, src));
+    if method_name(invocation, src) != "create" || !kind.ends_with("Property") {
+        return None;
+    }
+
+    let args = arguments(invocation);
+    let (first, mut rest) = args.split_first()?;
+    if first.kind() != "string_literal" {
+        return None;
+    }
+
+    let mut init = PropertyInit {
+        kind: kind.to_owned(),
+        name: string_value(*first, src),
+        bounds: Vec::new(),
+        enum_class: (kind == "DirectionProperty").then(|| "Direction".to_owned()),
+        filter: EnumFilter::All,
+    };
+
+    if kind == "IntegerProperty" {
+        init.bounds = rest
+            .iter()
+            .map(|a| text(unwrap_expression(*a), src).to_owned())
+            .collect();
+        return Some(init);
+    }
+
+    if let Some((class, tail)) = rest.split_first()
+        && class.kind() == "class_literal"
+    {
+        init.enum_class = named_children(*class)
+            .first()
+            .map(|t| text(*t, src).to_owned());
+        rest = tail;
+    }
+
+    init.filter = match rest {
+        [] => EnumFilter::All,
+        [arg] => enum_filter(unwrap_expression(*arg), src),
+        args => EnumFilter::Only(
+            args.iter()
+                .map(|a| simple_name(text(*a, src)).to_owned())
+                .collect(),
+        ),
+    };
+
+    Some(init)
+}
+
+fn enum_filter(arg: Node, src: &str) -> EnumFilter {
+    match arg.kind() {
+        "array_creation_expression" => EnumFilter::Only(
+            arg.child_by_field_name("value")
+                .map(named_children)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| simple_name(text(c, src)).to_owned())
+                .collect(),
+        ),
+        "field_access" | "identifier" => EnumFilter::Field(text(arg, src).to_owned()),
+        "lambda_expression" => {
+            let comparison = arg
+                .child_by_field_name("body")
+                .map(unwrap_expression)
+                .filter(|b| b.kind() == "binary_expression");
+            let Some(comparison) = comparison else {
+                return EnumFilter::All;
+            };
+
+            let operator = comparison
+                .child_by_field_name("operator")
+                .map(|o| text(o, src))
+                .unwrap_or_default();
+            let constant = ["right", "left"]
+                .into_iter()
+                .filter_map(|f| comparison.child_by_field_name(f))
+                .find(|n| n.kind() == "field_access")
+                .map(|n| simple_name(text(n, src)).to_owned());
+
+            match (operator, constant) {
+                ("!=", Some(c)) => EnumFilter::Except(vec![c]),
+                ("==", Some(c)) => EnumFilter::Only(vec![c]),
+                _ => EnumFilter::All,
+            }
+        }
+        _ => EnumFilter::All,
+    }
+}
+
+fn state_definition(method: Node, src: &str) -> StateDefinition {
+    let mut definition = StateDefinition {
+        added: Vec::new(),
+        calls_super: false,
+    };
+
+    for_each(method, &mut |node| {
+        if node.kind() != "method_invocation" {
+            return;
+        }
+        match method_name(node, src) {
+            "add" => {
+                for arg in arguments(node) {
+                    let arg = unwrap_expression(arg);
+                    let items = match arg.child_by_field_name("value") {
+                        Some(array) if arg.kind() == "array_creation_expression" => {
+                            named_children(array)
+                        }
+                        _ => vec![arg],
+                    };
+                    definition
+                        .added
+                        .extend(items.iter().map(|i| text(*i, src).to_owned()));
+                }
+            }
+            "createBlockStateDefinition" => {
+                definition.calls_super |= node
+                    .child_by_field_name("object")
+                    .is_some_and(|o| o.kind() == "super");
+            }
+            _ => {}
+        }
+    });
+
+    definition
+}
+
+fn default_state(constructor: Node, src: &str) -> Option<DefaultState> {
+    let call = find_map(constructor, &mut |node| {
+        (node.kind() == "method_invocation" && method_name(node, src) == "registerDefaultState")
+            .then_some(node)
+    })?;
+
+    let mut values = Vec::new();
+    let mut node = unwrap_expression(*arguments(call).first()?);
+
+    while node.kind() == "method_invocation" && method_name(node, src) == "setValue" {
+        if let [property, value] = arguments(node)[..] {
+            values.push((
+                text(unwrap_expression(property), src).to_owned(),
+                property_value(value, src),
+            ));
+        }
+        match node.child_by_field_name("object") {
+            Some(object) => node = unwrap_expression(object),
+            None => break,
+        }
+    }
+    values.reverse();
+
+    Some(DefaultState {
+        inherits: node.kind() == "method_invocation"
+            && method_name(node, src) == "defaultBlockState",
+        values,
+    })
+}
+
+fn property_value(node: Node, src: &str) -> Value {
+    let node = unwrap_expression(node);
+
+    match node.kind() {
+        "method_invocation" if method_name(node, src) == "valueOf" => {
+            match arguments(node).first() {
+                Some(arg) => property_value(*arg, src),
+                None => Value::Literal(text(node, src).to_owned()),
+            }
+        }
+        "identifier" | "field_access" => Value::Constant(simple_name(text(node, src)).to_owned()),
+        _ => Value::Literal(text(node, src).to_owned()),
+    }
+}
+
+/// Ids and classes of the blocks assigned to fields of `Blocks`.
+///
+/// The class is the first `X::new` or `new X(...)` naming a block class in the field's
+/// initializer, looking into the bodies of helper methods of `Blocks` it calls if needed.
+fn registered_blocks(root: Node, src: &str, sources: &mut Sources) -> Vec<(String, String)> {
+    let Some(body) = named_children(root)
+        .into_iter()
+        .find(|c| c.kind() == "class_declaration")
+        .and_then(|c| c.child_by_field_name("body"))
+    else {
+        return Vec::new();
+    };
+
+    let mut methods: HashMap<&str, Vec<Node>> = HashMap::new();
+    for member in named_children(body) {
+        if member.kind() == "method_declaration" {
+            methods
+                .entry(method_name(member, src))
+                .or_default()
+                .push(member);
+        }
+    }
+
+    let mut blocks = Vec::new();
+
+    for member in named_children(body) {
+        if member.kind() != "field_declaration" {
+            continue;
+        }
+
+        for declarator in named_children(member) {
+            let Some(value) = declarator.child_by_field_name("value") else {
+                continue;
+            };
+            let id = find_map(value, &mut |n| {
+                (n.kind() == "string_literal").then(|| string_value(n, src))
+            });
+            let Some(id) = id else {
+                continue;
+            };
+
+            let mut visited = HashSet::new();
+            if let Some(class) = block_class(value, src, &methods, &mut visited, sources) {
+                blocks.push((id, class));
+            }
+        }
+    }
+
+    blocks
+}
+
+fn block_class(
+    node: Node,
+    src: &str,
+    methods: &HashMap<&str, Vec<Node>>,
+    visited: &mut HashSet<usize>,
+    sources: &mut Sources,
+) -> Option<String> {
+    let constructed = match node.kind() {
+        "method_reference" => text(node, src).strip_suffix("::new"),
+        "object_creation_expression" => node.child_by_field_name("type").map(|t| text(t, src)),
+        _ => None,
+    };
+    if let Some(class) = constructed.map(|c| c.split('<').next().unwrap_or(c).trim())
+        && sources.is_block_class(class)
+    {
+        return Some(class.to_owned());
+    }
+
+    for child in named_children(node) {
+        if let Some(class) = block_class(child, src, methods, visited, sources) {
+            return Some(class);
+        }
+    }
+
+    if node.kind() != "method_invocation" {
+        return None;
+    }
+    let local = node
+        .child_by_field_name("object")
+        .is_none_or(|o| text(o, src) == "Blocks");
+    if !local {
+        return None;
+    }
+
+    for method in methods.get(method_name(node, src))? {
+        if visited.insert(method.id())
+            && let Some(class) = block_class(*method, src, methods, visited, sources)
+        {
+            return Some(class);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,22 @@
+pub mod animation;
+pub mod art;
+pub mod assets;
+pub mod baked;
+pub mod colors;
+pub mod cubes;
+pub mod error;
+pub mod java;
+pub mod matching;
+pub mod palette;
+pub mod registry;
+pub mod schema;
+pub mod schematic;
+pub mod shapes;
+pub mod source;
+pub mod textures;
+pub mod tint;
+pub mod variants;
+pub mod voxel;
+
+pub use assets::AssetStore;
+pub use error::{Diagnostics, Error};
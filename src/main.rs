@@ -1,151 +1,319 @@
-mod cubes;
-mod palette;
-mod schema;
-mod textures;
-mod variants;
+mod cli;
 
-use std::{collections::HashSet, fs, path::Path, process};
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+    process,
+};
 
+use clap::Parser;
+use image::imageops;
 use indexmap::IndexMap;
 
-use crate::cubes::{get_all_empty_blocks, get_all_full_cube_blocks};
-use crate::palette::{Material, MaterialDisplay, Palette};
-use crate::schema::{blockstate, model};
-use crate::textures::get_block_textures;
-use crate::variants::get_all_block_variants;
+use minecraft_blocks::{
+    AssetStore, Diagnostics, art,
+    colors::TextureImages,
+    matching::MatchFilter,
+    palette::{Palette, TEXTURES_DIR, VOLUMES_DIR},
+    schematic,
+    shapes::Shape,
+    source::DirSource,
+    tint::Climate,
+};
 
-const MC_DIR: &str = "mc_data/mc_assets/assets/minecraft";
+use crate::cli::{ArtArgs, Artifact, Cli, Command, DirArgs, PaletteOptions};
 
 fn main() {
-    let mc_dir = Path::new(MC_DIR);
+    let cli = Cli::parse();
 
-    let output_dir = Path::new("output");
-    let textures_dir = output_dir.join("textures");
+    match cli.command {
+        Command::Extract(args) => extract(&args.dirs, &args.palette, &args.emit),
+        Command::Palette(args) => extract(&args.dirs, &args.palette, &[Artifact::Palette]),
+        Command::Validate(args) => validate(&args.palette_dir),
+        Command::Art(args) => block_art(&args),
+    }
+}
 
-    fs::create_dir_all(&textures_dir).unwrap_or_else(|e| {
-        eprintln!("Failed to create directory structure: {}", e);
+fn extract(dirs: &DirArgs, palette_args: &PaletteOptions, emit: &[Artifact]) {
+    let output_dir = dirs.output_dir.as_path();
+    let textures_dir = output_dir.join(TEXTURES_DIR);
+    let volumes_dir = output_dir.join(VOLUMES_DIR);
+
+    let mut diagnostics = Diagnostics::default();
+
+    let mut assets = AssetStore::open(&dirs.assets, &mut diagnostics).unwrap_or_else(|e| {
+        eprintln!("Failed to load assets: {}", e);
         process::exit(1);
     });
 
-    let blockstates =
-        blockstate::load_all(mc_dir.join("blockstates")).expect("Blockstates should be valid");
-    let models =
-        model::load_all(mc_dir.join("models/block")).expect("Block models should be valid");
+    if let Some(java_src) = &dirs.java_src {
+        assets
+            .load_java_registry(java_src, &mut diagnostics)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to load block registry: {}", e);
+                process::exit(1);
+            });
+    }
+    if let Some(blocks_report) = &dirs.blocks_report {
+        assets
+            .load_blocks_report(blocks_report)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to load block report: {}", e);
+                process::exit(1);
+            });
+    }
+    assets.tints.climate = Climate {
+        temperature: palette_args.temperature,
+        downfall: palette_args.downfall,
+    };
+
+    let all_variants = assets.block_states();
+    let mut full_variants =
+        assets.full_cube_materials(palette_args.default_states_only, &mut diagnostics);
+    let mut volume_variants = palette_args.volume_resolution.map(|size| {
+        assets.volume_materials(
+            size as usize,
+            palette_args.default_states_only,
+            &mut diagnostics,
+        )
+    });
+    let empty_blocks = assets.empty_blocks(&mut diagnostics);
+    let shapes = if emit.contains(&Artifact::Shapes) {
+        assets.block_shapes(&mut diagnostics)
+    } else {
+        IndexMap::new()
+    };
 
-    let all_variants = get_all_block_variants(&blockstates);
+    // Before the report, so that textures which cannot be read count for --strict
+    if emit.contains(&Artifact::FullBlocks) || emit.contains(&Artifact::Palette) {
+        let mut colored = assets.add_colors(&mut full_variants, &mut diagnostics);
+        if let Some(volume_variants) = &mut volume_variants {
+            colored += assets.add_colors(volume_variants, &mut diagnostics);
+        }
+        println!("Computed colors of {} materials", colored);
+    }
 
-    let json_output = serde_json::to_string_pretty(&all_variants).unwrap();
-    let blocks_path = output_dir.join("blocks.json");
-    fs::write(&blocks_path, &json_output).unwrap_or_else(|e| {
-        eprintln!("Failed to write blocks.json: {}", e);
+    report(&diagnostics);
+    if dirs.strict && !diagnostics.is_empty() {
+        eprintln!("Aborting because of --strict");
         process::exit(1);
-    });
-    println!("Saved {} block variants", all_variants.len());
-
-    let full_cube_blocks = get_all_full_cube_blocks(&blockstates, &models);
-
-    let full_variants: IndexMap<String, Material> = all_variants
-        .into_iter()
-        .filter(|v| full_cube_blocks.contains(&v.name))
-        .map(|v| {
-            let texture = get_block_textures(
-                &v.name,
-                &v.blockstate.clone().unwrap_or_default(),
-                &models,
-                &blockstates,
-            );
-
-            let block_id = if let Some(b) = v.blockstate {
-                format!("{}#{}", v.name, b)
-            } else {
-                v.name.to_owned()
-            };
-
-            (
-                block_id,
-                Material {
-                    display: MaterialDisplay::Texture(texture),
-                    profile: None,
-                },
-            )
-        })
-        .collect();
+    }
 
-    let json_output = serde_json::to_string_pretty(&full_variants).unwrap();
-    let full_blocks_path = output_dir.join("full_blocks.json");
-    fs::write(&full_blocks_path, &json_output).unwrap_or_else(|e| {
-        eprintln!("Failed to write full_blocks.json: {}", e);
+    fs::create_dir_all(output_dir).unwrap_or_else(|e| {
+        eprintln!("Failed to create directory structure: {}", e);
         process::exit(1);
     });
-    println!("Saved {} full cube block variants", full_variants.len(),);
 
-    let empty_blocks = get_all_empty_blocks(&blockstates, &models);
+    if emit.contains(&Artifact::Blocks) {
+        write_json(&output_dir.join("blocks.json"), &all_variants);
+        println!("Saved {} block variants", all_variants.len());
+    }
+
+    if emit.contains(&Artifact::FullBlocks) {
+        write_json(
+            &output_dir.join("full_blocks.json"),
+            &full_variants.materials,
+        );
+        println!(
+            "Saved {} full cube block variants",
+            full_variants.materials.len()
+        );
+    }
+
+    if emit.contains(&Artifact::EmptyBlocks) {
+        write_json(&output_dir.join("empty_blocks.json"), &empty_blocks);
+        println!("Saved {} empty blocks", empty_blocks.len());
+    }
+
+    if emit.contains(&Artifact::Shapes) {
+        write_json(&output_dir.join("shapes.json"), &shapes);
+        println!("Saved {} block shapes", shapes.len());
+    }
+
+    if !emit.contains(&Artifact::Palette) {
+        return;
+    }
 
-    let json_output = serde_json::to_string_pretty(&empty_blocks).unwrap();
-    let empty_blocks_path = output_dir.join("empty_blocks.json");
-    fs::write(&empty_blocks_path, &json_output).unwrap_or_else(|e| {
-        eprintln!("Failed to write empty_blocks.json: {}", e);
+    fs::create_dir_all(&textures_dir).unwrap_or_else(|e| {
+        eprintln!("Failed to create directory structure: {}", e);
         process::exit(1);
     });
-    println!("Saved {} empty blocks", empty_blocks.len());
 
-    copy_textures_from_variants(
-        full_variants.values(),
-        &mc_dir.join("textures/block"),
-        &textures_dir,
-    );
+    let mut palette_materials = full_variants;
+    if let Some(volume_variants) = volume_variants {
+        palette_materials.extend(volume_variants);
+    }
+
+    let saved = palette_materials
+        .save_volumes(&volumes_dir)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to write volumes: {}", e);
+            process::exit(1);
+        });
+    if saved > 0 {
+        println!("Saved {} volumes", saved);
+    }
+
+    let (copied, failed) = assets.copy_textures(&palette_materials, &textures_dir);
+    println!("Saved {} textures", copied);
+    if failed > 0 {
+        println!("Failed to copy {} textures", failed);
+    }
 
     let palette = Palette {
-        name: "Minecraft Palette".to_owned(),
-        id: "minecraft".to_owned(),
-        materials: full_variants,
-        groups: IndexMap::new(),
+        name: palette_args.palette_name.clone(),
+        id: palette_args.palette_id.clone(),
+        materials: palette_materials.materials,
+        groups: palette_materials.groups,
         variant_sets: IndexMap::new(),
     };
 
-    palette.serialize_to_dir(output_dir, &textures_dir).unwrap();
-    Palette::deserialize_from_dir(output_dir.join("minecraft")).unwrap();
-}
+    palette
+        .serialize_to_dir(
+            output_dir,
+            &DirSource::new(output_dir),
+            TEXTURES_DIR,
+            VOLUMES_DIR,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to serialize palette: {}", e);
+            process::exit(1);
+        });
+    println!("Saved palette {}", palette.id);
 
-fn copy_textures_from_variants<'a>(
-    variants: impl Iterator<Item = &'a Material>,
-    source_dir: &Path,
-    output_dir: &Path,
-) {
-    let mut textures = HashSet::new();
+    validate(&output_dir.join(&palette.id));
+}
 
-    for variant in variants {
-        if let MaterialDisplay::Texture(ts) = &variant.display {
-            let ts = [&ts.z, &ts.nz, &ts.y, &ts.ny, &ts.x, &ts.nx];
+fn report(diagnostics: &Diagnostics) {
+    if diagnostics.is_empty() {
+        return;
+    }
 
-            for t in ts {
-                textures.insert(t.path.clone());
-            }
-        }
+    for error in diagnostics.errors() {
+        eprintln!("{}", error);
     }
+    eprintln!("{} problems found", diagnostics.len());
+}
+
+fn validate(palette_dir: &Path) {
+    let palette = Palette::deserialize_from_dir(palette_dir).unwrap_or_else(|e| {
+        eprintln!("Invalid palette {}: {}", palette_dir.display(), e);
+        process::exit(1);
+    });
 
-    let mut copied = 0;
-    let mut failed = 0;
+    println!(
+        "Palette {} is valid: {} materials, {} groups, {} variant sets",
+        palette.id,
+        palette.materials.len(),
+        palette.groups.len(),
+        palette.variant_sets.len()
+    );
+}
 
-    for t in textures {
-        let source_path = source_dir.join(&t).with_extension("png");
-        let output_path = output_dir.join(&t).with_extension("png");
+fn block_art(args: &ArtArgs) {
+    let mut image = image::open(&args.image)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to read {}: {}", args.image.display(), e);
+            process::exit(1);
+        })
+        .to_rgba8();
 
-        if Path::new(&source_path).exists() {
-            if let Err(e) = fs::copy(&source_path, &output_path) {
-                eprintln!("Failed to copy {}: {}", source_path.display(), e);
-                failed += 1;
-            } else {
-                copied += 1;
-            }
+    if args.width.is_some() || args.height.is_some() {
+        let (width, height) = (image.width() as u64, image.height() as u64);
+        let scaled = |size: Option<u32>, other: Option<u32>, from: u64, to: u64| {
+            size.unwrap_or_else(|| {
+                let other = other.unwrap() as u64;
+                ((other * from + to / 2) / to).max(1) as u32
+            })
+        };
+        let new_width = scaled(args.width, args.height, width, height);
+        let new_height = scaled(args.height, args.width, height, width);
+        image = imageops::resize(
+            &image,
+            new_width,
+            new_height,
+            imageops::FilterType::Triangle,
+        );
+    }
+
+    let mut palette = Palette::deserialize_from_dir(&args.palette_dir).unwrap_or_else(|e| {
+        eprintln!("Invalid palette {}: {}", args.palette_dir.display(), e);
+        process::exit(1);
+    });
+    let mut diagnostics = Diagnostics::default();
+    let colored = palette.add_colors(&args.palette_dir, &mut diagnostics);
+    report(&diagnostics);
+    if colored > 0 {
+        println!("Computed colors of {} materials", colored);
+    }
+
+    let facing = art::Facing::from(args.facing);
+    let filter = MatchFilter {
+        face: facing.face(),
+        groups: args.groups.clone(),
+        shapes: if args.include_volumes {
+            Vec::new()
         } else {
-            eprintln!("Texture file not found: {}", source_path.display());
-            failed += 1;
-        }
+            vec![Shape::FullCube]
+        },
+        block_shapes: None,
+    };
+    let index = palette.color_index(args.delta_e.into(), &filter);
+    if index.is_empty() {
+        eprintln!("No material of palette {} can be used", palette.id);
+        process::exit(1);
     }
 
-    println!("Saved {} textures", copied);
-    if failed > 0 {
-        println!("Failed to copy {} textures", failed);
+    let block_art = art::block_art(&image, &index, facing, args.dither.into());
+    write_json(&args.output, &block_art);
+    let used: BTreeSet<&str> = block_art
+        .rows
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|id| id.as_str())
+        .collect();
+    println!(
+        "Saved block art of {}x{} blocks using {} materials",
+        block_art.width,
+        block_art.height,
+        used.len()
+    );
+
+    if let Some(path) = &args.preview {
+        let files = DirSource::new(&args.palette_dir);
+        let preview =
+            art::render_preview(&block_art, &palette, &mut TextureImages::palette(&files))
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to render preview: {}", e);
+                    process::exit(1);
+                });
+        preview.save(path).unwrap_or_else(|e| {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            process::exit(1);
+        });
+        println!("Saved preview to {}", path.display());
+    }
+
+    if let Some(path) = &args.schematic {
+        File::create(path)
+            .and_then(|file| {
+                schematic::write_schematic(&block_art, args.data_version, BufWriter::new(file))
+            })
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+                process::exit(1);
+            });
+        println!("Saved schematic to {}", path.display());
     }
 }
+
+fn write_json<T: serde::Serialize + ?Sized>(path: &Path, value: &T) {
+    let json_output = serde_json::to_string_pretty(value).unwrap();
+    fs::write(path, &json_output).unwrap_or_else(|e| {
+        eprintln!("Failed to write {}: {}", path.display(), e);
+        process::exit(1);
+    });
+}
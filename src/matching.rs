@@ -0,0 +1,383 @@
+//! Materials of a palette closest to a color, compared in a perceptual color space.
+//!
+//! Materials are placed in an R-tree by the color they show, which answers queries without
+//! looking at every material. Alpha is a fourth coordinate scaled like lightness, so
+//! translucent colors match translucent materials.
+
+use std::collections::BTreeSet;
+
+use indexmap::IndexMap;
+use rstar::{RTree, primitives::GeomWithData};
+
+use crate::{
+    palette::{Color, MaterialColors, MaterialDisplay, Palette},
+    shapes::Shape,
+};
+
+/// Largest chroma of an sRGB color in CIELAB, that of pure blue with a margin.
+const MAX_CHROMA: f64 = 135.0;
+
+/// Color difference formula, which also picks the color space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeltaE {
+    /// Euclidean distance in OKLab
+    #[default]
+    Ok,
+    /// Euclidean distance in CIELAB, CIE 1976
+    Cie76,
+    /// CIE 1994 in CIELAB, with the graphic arts weights
+    Cie94,
+    /// CIEDE2000 in CIELAB
+    Ciede2000,
+}
+
+impl DeltaE {
+    /// Coordinates of `color` in the color space of the formula, with alpha scaled to the
+    /// range of lightness.
+    fn coordinates(self, color: Color) -> [f64; 4] {
+        let [l, a, b] = match self {
+            DeltaE::Ok => oklab(color),
+            _ => cielab(color),
+        };
+        let lightness_range = if self == DeltaE::Ok { 1.0 } else { 100.0 };
+
+        [l, a, b, color.a as f64 / 255.0 * lightness_range]
+    }
+
+    /// Difference between the coordinates of the `reference` color and a `sample`.
+    fn difference(self, reference: [f64; 4], sample: [f64; 4]) -> f64 {
+        let [l1, a1, b1, alpha1] = reference;
+        let [l2, a2, b2, alpha2] = sample;
+
+        let color = match self {
+            DeltaE::Ok | DeltaE::Cie76 => return euclidean(reference, sample),
+            DeltaE::Cie94 => cie94([l1, a1, b1], [l2, a2, b2]),
+            DeltaE::Ciede2000 => ciede2000([l1, a1, b1], [l2, a2, b2]),
+        };
+        color.hypot(alpha1 - alpha2)
+    }
+
+    /// How many times the Euclidean distance between the coordinates of `reference` and any
+    /// sample can be their [`DeltaE::difference`] at most, which bounds the search of the
+    /// tree for the nearest materials.
+    fn euclidean_factor(self, reference: [f64; 4]) -> f64 {
+        match self {
+            DeltaE::Ok | DeltaE::Cie76 => 1.0,
+            // Lightness is not weighted, and the chroma and hue differences make up the
+            // Euclidean a and b difference weighted by at least 1 / SC of the reference
+            DeltaE::Cie94 => 1.0 + 0.045 * reference[1].hypot(reference[2]),
+            DeltaE::Ciede2000 => {
+                // The primed a and b differences make up at least the a and b difference.
+                // Weights are smallest with the largest chroma, at most 1.5 times that of
+                // sRGB, and lightness from 0 to 100, and the rotation term takes at most
+                // sin(60°) of the chroma and hue part
+                let c = 1.5 * MAX_CHROMA;
+                let sl: f64 = 1.0 + 0.015 * 2500.0 / 2520.0_f64.sqrt();
+                let sc = 1.0 + 0.045 * c;
+                let sh = 1.0 + 0.015 * c * 1.93;
+                let rotation = 1.0 - 3.0_f64.sqrt() / 2.0;
+                let weight = (1.0 / (sl * sl)).min(rotation / (sc * sc).max(sh * sh));
+                1.0 / weight.sqrt()
+            }
+        }
+    }
+}
+
+/// Face of a material whose color is matched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MatchFace {
+    /// The average over every face
+    #[default]
+    All,
+    Top,
+    Bottom,
+    North,
+    South,
+    East,
+    West,
+}
+
+impl MatchFace {
+    pub fn color(self, colors: &MaterialColors) -> Color {
+        match self {
+            MatchFace::All => colors.average,
+            MatchFace::Top => colors.faces.y,
+            MatchFace::Bottom => colors.faces.ny,
+            MatchFace::North => colors.faces.z,
+            MatchFace::South => colors.faces.nz,
+            MatchFace::East => colors.faces.x,
+            MatchFace::West => colors.faces.nx,
+        }
+    }
+}
+
+/// Which materials of a palette a [`ColorIndex`] holds, and by which color.
+#[derive(Clone, Debug, Default)]
+pub struct MatchFilter<'a> {
+    pub face: MatchFace,
+    /// Only members of these groups, see [`Palette::group_members`]. Every material if empty
+    pub groups: Vec<String>,
+    /// Only materials of these shapes. Every material if empty
+    pub shapes: Vec<Shape>,
+    /// Shapes of the blocks of volume materials by block id, as in `shapes.json`.
+    /// Textured materials are full cubes, volumes not found here have no shape
+    pub block_shapes: Option<&'a IndexMap<String, Shape>>,
+}
+
+impl MatchFilter<'_> {
+    fn shape(&self, id: &str, display: &MaterialDisplay) -> Option<Shape> {
+        match display {
+            MaterialDisplay::Texture(_) | MaterialDisplay::TextureAnimation { .. } => {
+                Some(Shape::FullCube)
+            }
+            _ => {
+                // Weighted alternatives are `<block id>@<index>`
+                let block_id = id.rsplit_once('@').map_or(id, |(block, _)| block);
+                self.block_shapes?.get(block_id).copied()
+            }
+        }
+    }
+}
+
+/// A material matched to a color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorMatch<'a> {
+    pub id: &'a str,
+    /// The color of the material that was matched
+    pub color: Color,
+    /// Difference to the queried color in the units of the [`DeltaE`] formula
+    pub distance: f64,
+}
+
+/// Materials of a palette indexed by the color they show, see [`Palette::color_index`].
+pub struct ColorIndex {
+    delta_e: DeltaE,
+    tree: RTree<GeomWithData<[f64; 4], usize>>,
+    materials: Vec<(String, Color)>,
+}
+
+impl ColorIndex {
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// The material closest to `color`, `None` if the index is empty.
+    pub fn nearest(&self, color: Color) -> Option<ColorMatch<'_>> {
+        self.nearest_n(color, 1).into_iter().next()
+    }
+
+    /// Up to `count` materials closest to `color`, closest first and equally close ones
+    /// by id.
+    ///
+    /// The tree is searched in Euclidean distance until no material further away can be
+    /// closer by the difference formula, so CIE94 and CIEDE2000 look at more materials than
+    /// the Euclidean formulas.
+    pub fn nearest_n(&self, color: Color, count: usize) -> Vec<ColorMatch<'_>> {
+        let reference = self.delta_e.coordinates(color);
+        let factor = self.delta_e.euclidean_factor(reference);
+        let order =
+            |a: &ColorMatch, b: &ColorMatch| a.distance.total_cmp(&b.distance).then(a.id.cmp(b.id));
+
+        let mut matches: Vec<ColorMatch> = Vec::with_capacity(count + 1);
+        if count == 0 {
+            return matches;
+        }
+
+        for point in self.tree.nearest_neighbor_iter(&reference) {
+            let sample = *point.geom();
+            if matches.len() == count
+                && euclidean(reference, sample) / factor > matches[count - 1].distance
+            {
+                break;
+            }
+
+            let (id, color) = &self.materials[point.data];
+            let candidate = ColorMatch {
+                id,
+                color: *color,
+                distance: self.delta_e.difference(reference, sample),
+            };
+            let at = matches.partition_point(|m| order(m, &candidate).is_lt());
+            if at < count {
+                matches.insert(at, candidate);
+                matches.truncate(count);
+            }
+        }
+
+        matches
+    }
+}
+
+impl Palette {
+    /// Indexes the materials passing `filter` by their color for matching with `delta_e`.
+    ///
+    /// Materials without [`MaterialColors`] are left out, so compute them first with
+    /// [`Palette::add_colors`] if `materials.json` may lack them.
+    pub fn color_index(&self, delta_e: DeltaE, filter: &MatchFilter) -> ColorIndex {
+        let members: Option<BTreeSet<&str>> = (!filter.groups.is_empty()).then(|| {
+            filter
+                .groups
+                .iter()
+                .flat_map(|g| self.group_members(g))
+                .collect()
+        });
+
+        let mut materials = Vec::new();
+        let mut points = Vec::new();
+
+        for (id, material) in &self.materials {
+            let Some(colors) = &material.colors else {
+                continue;
+            };
+            if members.as_ref().is_some_and(|m| !m.contains(id.as_str())) {
+                continue;
+            }
+            if !filter.shapes.is_empty()
+                && !filter
+                    .shape(id, &material.display)
+                    .is_some_and(|s| filter.shapes.contains(&s))
+            {
+                continue;
+            }
+
+            let color = filter.face.color(colors);
+            points.push(GeomWithData::new(
+                delta_e.coordinates(color),
+                materials.len(),
+            ));
+            materials.push((id.clone(), color));
+        }
+
+        ColorIndex {
+            delta_e,
+            tree: RTree::bulk_load(points),
+            materials,
+        }
+    }
+}
+
+fn euclidean(a: [f64; 4], b: [f64; 4]) -> f64 {
+    (0..4).map(|i| (a[i] - b[i]).powi(2)).sum::<f64>().sqrt()
+}
+
+fn linear_rgb(color: Color) -> [f64; 3] {
+    [color.r, color.g, color.b].map(|c| {
+        let c = c as f64 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    })
+}
+
+/// OKLab coordinates of an sRGB color, lightness from 0 to 1.
+pub fn oklab(color: Color) -> [f64; 3] {
+    let [r, g, b] = linear_rgb(color);
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// CIELAB coordinates of an sRGB color under D65, lightness from 0 to 100.
+pub fn cielab(color: Color) -> [f64; 3] {
+    let [r, g, b] = linear_rgb(color);
+
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+
+    let f = |t: f64| {
+        const DELTA: f64 = 6.0 / 29.0;
+        if t > DELTA.powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// CIE 1994 difference of `sample` from `reference` in CIELAB, with the graphic arts
+/// weights.
+pub fn cie94([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let dl = l1 - l2;
+    let dc = c1 - c2;
+    let dh2 = ((a1 - a2).powi(2) + (b1 - b2).powi(2) - dc * dc).max(0.0);
+
+    let sc = 1.0 + 0.045 * c1;
+    let sh = 1.0 + 0.015 * c1;
+
+    (dl * dl + (dc / sc).powi(2) + dh2 / (sh * sh)).sqrt()
+}
+
+/// CIEDE2000 difference of two colors in CIELAB, with all weights 1.
+pub fn ciede2000([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    let pow7 = |c: f64| c.powi(7);
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt());
+
+    let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos = |degrees: f64| degrees.to_radians().cos();
+    let t =
+        1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar) + 0.32 * cos(3.0 * h_bar + 6.0)
+            - 0.20 * cos(4.0 * h_bar - 63.0);
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt();
+
+    let sl = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+}
@@ -1,13 +1,27 @@
 use std::{
-    collections::{BTreeSet, HashSet},
-    fs,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt, fs, io, ops,
     path::Path,
+    str::FromStr,
     time::Duration,
 };
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    colors,
+    error::{Diagnostics, Error},
+    schema::location::ResourceLocation,
+    source::{self, AssetSource},
+    voxel::{VOXEL_EXTENSION, VoxelGrid},
+};
+
+/// Directory of a palette holding the textures of its materials.
+pub const TEXTURES_DIR: &str = "textures";
+/// Directory of a palette holding the voxel files of its volume materials.
+pub const VOLUMES_DIR: &str = "volumes";
+
 pub struct Palette {
     pub name: String,
     pub id: String,
@@ -29,17 +43,17 @@ impl Palette {
         let name = id.clone();
 
         // TODO: this doesn't error on duplicate keys (serde default behavior) and I hate every solution I've found (including forking indexmap)
-        let materials_json = fs::read_to_string(&palette_dir.join("materials.json"))
+        let materials_json = fs::read_to_string(palette_dir.join("materials.json"))
             .map_err(|e| format!("Failed to read materials.json: {}", e))?;
         let materials = serde_json::from_str(&materials_json)
             .map_err(|e| format!("Failed to parse materials.json: {}", e))?;
 
-        let groups_json = fs::read_to_string(&palette_dir.join("groups.json"))
+        let groups_json = fs::read_to_string(palette_dir.join("groups.json"))
             .map_err(|e| format!("Failed to read groups.json: {}", e))?;
         let groups = serde_json::from_str(&groups_json)
             .map_err(|e| format!("Failed to parse groups.json: {}", e))?;
 
-        let variant_sets_json = fs::read_to_string(&palette_dir.join("variant_sets.json"))
+        let variant_sets_json = fs::read_to_string(palette_dir.join("variant_sets.json"))
             .map_err(|e| format!("Failed to read variant_sets.json: {}", e))?;
         let variant_sets = serde_json::from_str(&variant_sets_json)
             .map_err(|e| format!("Failed to parse variant_sets.json: {}", e))?;
@@ -52,17 +66,44 @@ impl Palette {
             variant_sets,
         };
 
-        let textures_dir = palette_dir.join("textures");
-        if textures_dir.exists() {
-            let mut texture_paths = HashSet::new();
-            let mut missing_textures = Vec::new();
-
-            for (_, material) in &palette.materials {
-                palette.visit_material(&material.display, &mut |path| {
+        let mut texture_paths = HashSet::new();
+        let mut volume_paths = HashSet::new();
+        for (_, material) in &palette.materials {
+            palette.visit_material(&material.display, &mut |file| match file {
+                MaterialFile::Texture(path) => {
                     texture_paths.insert(path.to_string());
-                });
+                }
+                MaterialFile::Volume(path) => {
+                    volume_paths.insert(path.to_string());
+                }
+            });
+        }
+
+        let volumes_dir = palette_dir.join(VOLUMES_DIR);
+        let mut missing_volumes = Vec::new();
+
+        for volume_path in volume_paths {
+            let volume_file = volumes_dir.join(format!("{}.{}", volume_path, VOXEL_EXTENSION));
+            if !volume_file.exists() {
+                missing_volumes.push(volume_path);
+                continue;
             }
 
+            let grid = fs::File::open(&volume_file)
+                .map(io::BufReader::new)
+                .and_then(VoxelGrid::read_from)
+                .map_err(|e| format!("Invalid volume {}: {}", volume_path, e))?;
+            texture_paths.extend(grid.textures.iter().map(|t| t.file_stem()));
+        }
+
+        if !missing_volumes.is_empty() {
+            eprintln!("Warning: Missing volumes: {}", missing_volumes.join(", "));
+        }
+
+        let textures_dir = palette_dir.join(TEXTURES_DIR);
+        if textures_dir.exists() {
+            let mut missing_textures = Vec::new();
+
             for texture_path in texture_paths {
                 let texture_file = textures_dir.join(format!("{}.png", texture_path));
                 if !texture_file.exists() {
@@ -80,10 +121,80 @@ impl Palette {
         Ok(palette)
     }
 
+    /// Ids in group `id`, with variant sets resolved to the ids they take. Empty if there
+    /// is no such group.
+    pub fn group_members(&self, id: &str) -> BTreeSet<&str> {
+        let mut members = BTreeSet::new();
+        if let Some(group) = self.groups.get(id) {
+            self.collect_block_ids(&group.block_ids, &mut members, &mut HashSet::new());
+        }
+        members
+    }
+
+    fn collect_block_ids<'a>(
+        &'a self,
+        block_ids: &'a BlockIds,
+        members: &mut BTreeSet<&'a str>,
+        visited_sets: &mut HashSet<&'a str>,
+    ) {
+        match block_ids {
+            BlockIds::Blocks(ids) => members.extend(ids.iter().map(String::as_str)),
+            BlockIds::VariantSet(name) => {
+                if visited_sets.insert(name)
+                    && let Some(set) = self.variant_sets.get(name)
+                {
+                    self.collect_block_ids(&set.input_block_ids, members, visited_sets);
+                }
+            }
+        }
+    }
+
+    /// Computes the [`MaterialColors`] of the materials that have none yet from the
+    /// textures and volumes in `palette_dir`, see [`crate::colors`].
+    ///
+    /// Materials whose files cannot be read are reported to `diagnostics`. Returns the
+    /// number of materials colored.
+    pub fn add_colors<P: AsRef<Path>>(
+        &mut self,
+        palette_dir: P,
+        diagnostics: &mut Diagnostics,
+    ) -> usize {
+        let files = source::DirSource::new(palette_dir);
+        let mut images = colors::TextureImages::palette(&files);
+        let mut grids: HashMap<String, VoxelGrid> = HashMap::new();
+
+        let mut volume = |path: &str| {
+            if let Some(grid) = grids.get(path) {
+                return Ok(grid.clone());
+            }
+
+            let file = source::join(VOLUMES_DIR, &format!("{}.{}", path, VOXEL_EXTENSION));
+            let grid = files
+                .read(&file)
+                .and_then(|bytes| VoxelGrid::read_from(bytes.as_slice()))
+                .map_err(|e| Error::Io {
+                    path: file,
+                    source: e,
+                })?;
+            grids.insert(path.to_owned(), grid.clone());
+            Ok(grid)
+        };
+        colors::add_colors(
+            self.materials.iter_mut(),
+            &mut images,
+            &mut volume,
+            diagnostics,
+        )
+    }
+
+    /// Writes the palette to `output_dir/<id>`, reading the textures and volume files its
+    /// materials refer to from `textures_dir` and `volumes_dir` in `files`.
     pub fn serialize_to_dir<P: AsRef<Path>>(
         &self,
         output_dir: P,
-        textures_dir: P,
+        files: &dyn AssetSource,
+        textures_dir: &str,
+        volumes_dir: &str,
     ) -> Result<(), String> {
         let palette_dir = output_dir.as_ref().join(&self.id);
         fs::create_dir_all(&palette_dir)
@@ -106,31 +217,75 @@ impl Palette {
                 .map_err(|e| format!("Failed to write variant_sets.json: {}", e))?;
         }
 
-        let output_textures_dir = palette_dir.join("textures");
-        fs::create_dir_all(&output_textures_dir)
-            .map_err(|e| format!("Failed to create textures directory: {}", e))?;
+        let mut texture_paths = HashSet::new();
+        let mut volume_paths = HashSet::new();
+        for (_, material) in &self.materials {
+            self.visit_material(&material.display, &mut |file| match file {
+                MaterialFile::Texture(path) => {
+                    texture_paths.insert(path.to_string());
+                }
+                MaterialFile::Volume(path) => {
+                    volume_paths.insert(path.to_string());
+                }
+            });
+        }
 
         {
-            let mut texture_paths = HashSet::new();
-            let mut missing_textures = Vec::new();
+            let mut volumes = Vec::new();
+            let mut missing_volumes = Vec::new();
+
+            for volume_path in volume_paths {
+                let src =
+                    source::join(volumes_dir, &format!("{}.{}", volume_path, VOXEL_EXTENSION));
+                if !files.exists(&src) {
+                    missing_volumes.push(volume_path);
+                    continue;
+                }
 
-            for (_, material) in &self.materials {
-                self.visit_material(&material.display, &mut |path| {
-                    let src = textures_dir.as_ref().join(format!("{}.png", path));
-                    if src.exists() {
-                        texture_paths.insert(path.to_string());
-                    } else {
-                        missing_textures.push(path.to_string());
-                    }
-                });
+                let bytes = files
+                    .read(&src)
+                    .map_err(|e| format!("Failed to read volume {}: {}", volume_path, e))?;
+                let grid = VoxelGrid::read_from(bytes.as_slice())
+                    .map_err(|e| format!("Invalid volume {}: {}", volume_path, e))?;
+
+                texture_paths.extend(grid.textures.iter().map(|t| t.file_stem()));
+                volumes.push((volume_path, bytes));
+            }
+
+            if !missing_volumes.is_empty() {
+                return Err(format!("Missing volumes: {}", missing_volumes.join(", ")));
+            }
+
+            let output_volumes_dir = palette_dir.join(VOLUMES_DIR);
+            for (volume_path, bytes) in volumes {
+                let dst = output_volumes_dir.join(format!("{}.{}", volume_path, VOXEL_EXTENSION));
+
+                if let Some(parent) = dst.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create volume subdirectory: {}", e))?;
+                }
+                fs::write(&dst, bytes)
+                    .map_err(|e| format!("Failed to copy volume {}: {}", volume_path, e))?;
             }
+        }
+
+        let output_textures_dir = palette_dir.join(TEXTURES_DIR);
+        fs::create_dir_all(&output_textures_dir)
+            .map_err(|e| format!("Failed to create textures directory: {}", e))?;
+
+        {
+            let missing_textures: Vec<&String> = texture_paths
+                .iter()
+                .filter(|path| !files.exists(&source::join(textures_dir, &format!("{}.png", path))))
+                .collect();
 
             if !missing_textures.is_empty() {
-                return Err(format!("Missing textures: {}", missing_textures.join(", ")));
+                let missing: Vec<&str> = missing_textures.iter().map(|p| p.as_str()).collect();
+                return Err(format!("Missing textures: {}", missing.join(", ")));
             }
 
             for texture_path in texture_paths {
-                let src = textures_dir.as_ref().join(format!("{}.png", texture_path));
+                let src = source::join(textures_dir, &format!("{}.png", texture_path));
                 let dst = output_textures_dir.join(format!("{}.png", texture_path));
 
                 if let Some(parent) = dst.parent() {
@@ -138,7 +293,10 @@ impl Palette {
                         .map_err(|e| format!("Failed to create texture subdirectory: {}", e))?;
                 }
 
-                fs::copy(&src, &dst)
+                let bytes = files
+                    .read(&src)
+                    .map_err(|e| format!("Failed to read texture {}: {}", texture_path, e))?;
+                fs::write(&dst, bytes)
                     .map_err(|e| format!("Failed to copy texture {}: {}", texture_path, e))?;
             }
         }
@@ -148,7 +306,7 @@ impl Palette {
 
     fn visit_material<F>(&self, display: &MaterialDisplay, visitor: &mut F)
     where
-        F: FnMut(&str),
+        F: FnMut(MaterialFile),
     {
         match display {
             MaterialDisplay::Texture(tex) => {
@@ -160,11 +318,11 @@ impl Palette {
                 }
             }
             MaterialDisplay::Volume(vol) => {
-                visitor(&vol.path);
+                visitor(MaterialFile::Volume(&vol.path));
             }
             MaterialDisplay::VolumeAnimation { frames, .. } => {
                 for frame in frames {
-                    visitor(&frame.path);
+                    visitor(MaterialFile::Volume(&frame.path));
                 }
             }
         }
@@ -172,22 +330,59 @@ impl Palette {
 
     fn visit_block_texture_paths<F>(&self, tex: &BlockTexture, visitor: &mut F)
     where
-        F: FnMut(&str),
+        F: FnMut(MaterialFile),
     {
-        visitor(&tex.x.path);
-        visitor(&tex.nx.path);
-        visitor(&tex.y.path);
-        visitor(&tex.ny.path);
-        visitor(&tex.z.path);
-        visitor(&tex.nz.path);
+        visitor(MaterialFile::Texture(&tex.x.path.file_stem()));
+        visitor(MaterialFile::Texture(&tex.nx.path.file_stem()));
+        visitor(MaterialFile::Texture(&tex.y.path.file_stem()));
+        visitor(MaterialFile::Texture(&tex.ny.path.file_stem()));
+        visitor(MaterialFile::Texture(&tex.z.path.file_stem()));
+        visitor(MaterialFile::Texture(&tex.nz.path.file_stem()));
     }
 }
 
+/// A file in the palette directory a material refers to, by path without extension.
+enum MaterialFile<'a> {
+    /// `textures/<path>.png`
+    Texture(&'a str),
+    /// `volumes/<path>.voxel`, see [`crate::voxel`]
+    Volume(&'a str),
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Material {
     pub display: MaterialDisplay,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub profile: Option<MaterialProfile>,
+    /// Names of the resource packs the textures were read from, in stacking order
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub packs: Vec<String>,
+    /// Colors of the pixels the material shows, see [`crate::colors`]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub colors: Option<MaterialColors>,
+}
+
+/// Representative colors of a material, for tools that do not render its textures.
+///
+/// Averages have the mean alpha of their pixels, so faces showing nothing are transparent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MaterialColors {
+    /// Mean of the visible pixels weighted by their alpha
+    pub average: Color,
+    /// Center of the heaviest cluster of similar pixels
+    pub dominant: Color,
+    /// Average color of each face
+    pub faces: FaceColors,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FaceColors {
+    pub x: Color,
+    pub nx: Color,
+    pub y: Color,
+    pub ny: Color,
+    pub z: Color,
+    pub nz: Color,
 }
 
 // TODO: default
@@ -203,6 +398,7 @@ pub struct MaterialProfile {
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum MaterialDisplay {
     Texture(BlockTexture),
     TextureAnimation {
@@ -216,7 +412,7 @@ pub enum MaterialDisplay {
     },
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BlockTexture {
     #[serde(
         serialize_with = "serialize_face_string",
@@ -267,14 +463,17 @@ where
 
 fn parse_face_texture(s: &str) -> Result<FaceTexture, String> {
     let (path, tags) = if let Some((p, t)) = s.split_once('#') {
-        (p.to_string(), Some(t))
+        (p, Some(t))
     } else {
-        (s.to_string(), None)
+        (s, None)
     };
+    let path = path.parse()?;
 
     let mut rotation = Rotation::CCW0;
     let mut flip_x = false;
     let mut flip_y = false;
+    let mut uv = None;
+    let mut tint = None;
 
     if let Some(tags) = tags {
         for tag in tags.split(default_variant_tags::SEP) {
@@ -291,6 +490,19 @@ fn parse_face_texture(s: &str) -> Result<FaceTexture, String> {
                 flip_x = true;
             } else if tag == default_variant_tags::FLIP_Y_TEX {
                 flip_y = true;
+            } else if let Some(uv_str) = tag.strip_prefix(default_variant_tags::UV_TEX) {
+                let coords = uv_str
+                    .split(',')
+                    .map(|c| c.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Invalid UV: {}", e))?;
+                uv = Some(
+                    coords
+                        .try_into()
+                        .map_err(|_| format!("UV needs 4 coordinates: {}", uv_str))?,
+                );
+            } else if let Some(hex) = tag.strip_prefix(default_variant_tags::TINT_TEX) {
+                tint = Some(Color::from_hex(hex).map_err(|e| format!("Invalid tint: {}", e))?);
             }
         }
     }
@@ -300,40 +512,133 @@ fn parse_face_texture(s: &str) -> Result<FaceTexture, String> {
         rotation,
         flip_x,
         flip_y,
+        uv,
+        tint,
     })
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+/// Texture of one face of a block.
+///
+/// The texture is cropped to `uv`, then mirrored as `flip_x` and `flip_y` say, then turned
+/// counter-clockwise by `rotation`. Mirroring both ways looks the same as turning by 180
+/// degrees.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FaceTexture {
-    pub path: String,
+    pub path: ResourceLocation,
     #[serde(skip_serializing_if = "is_rotation_zero", default)]
     pub rotation: Rotation,
     #[serde(skip_serializing_if = "is_false", default)]
     pub flip_x: bool,
     #[serde(skip_serializing_if = "is_false", default)]
     pub flip_y: bool,
+    /// Region of the texture shown on the face as `[u1, v1, u2, v2]` in pixels, the whole
+    /// texture if `None`. `u1 > u2` or `v1 > v2` mirror the region, as in block models.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub uv: Option<[f32; 4]>,
+    /// Color multiplied into the texture, as the game tints faces with a `tintindex`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tint: Option<Color>,
 }
 
 impl FaceTexture {
-    pub fn new(path: String) -> Self {
+    pub fn new(path: ResourceLocation) -> Self {
         Self {
             path,
             rotation: Rotation::CCW0,
             flip_x: false,
             flip_y: false,
+            uv: None,
+            tint: None,
         }
     }
 
+    pub fn with_tint(self, tint: Option<Color>) -> Self {
+        Self { tint, ..self }
+    }
+
+    /// Shows only the `uv` region of the texture, keeping `None` for the whole texture.
+    pub fn with_uv(self, uv: Option<[f32; 4]>) -> Self {
+        Self {
+            uv: uv.filter(|uv| *uv != [0.0, 0.0, 16.0, 16.0]),
+            ..self
+        }
+    }
+
+    /// Pixel columns and rows the UV region covers in a texture of `width`×`height`
+    /// pixels, at least one of each even for a degenerate region.
+    pub fn uv_pixels(&self, width: u32, height: u32) -> (ops::Range<u32>, ops::Range<u32>) {
+        let [u1, v1, u2, v2] = self.uv.unwrap_or([0.0, 0.0, 16.0, 16.0]);
+        let range = |a: f32, b: f32, size: u32| {
+            let pixel = |c: f32| ((c / 16.0 * size as f32).round() as u32).min(size);
+            let start = pixel(a.min(b)).min(size - 1);
+            start..pixel(a.max(b)).max(start + 1)
+        };
+        (range(u1, u2, width), range(v1, v2, height))
+    }
+
+    /// Turns the face texture as a blockstate rotation turns the face, see [`BlockTexture`].
+    ///
+    /// With `uvlock` the texture keeps its orientation and the UV region turns along with
+    /// the face instead, so it still covers the part of the texture matching the face's
+    /// position in the world, as the game recomputes locked UVs.
+    pub fn turn(&self, rotation: Rotation, uvlock: bool) -> Self {
+        if !uvlock {
+            return self.add_rotation(rotation);
+        }
+
+        // A mirrored texture shows the world mirrored, so the region turns the other way
+        let turns = if self.flip_x != self.flip_y {
+            rotation.degrees() / 90
+        } else {
+            (-rotation).degrees() / 90
+        };
+        let mut uv = self.uv;
+        if let Some(uv) = &mut uv {
+            for _ in 0..turns {
+                *uv = turn_uv_clockwise(*uv);
+            }
+        }
+        Self { uv, ..self.clone() }
+    }
+
+    /// Turns the texture further counter-clockwise, after cropping and mirroring it.
     pub fn add_rotation(&self, rotation: Rotation) -> Self {
         Self {
-            path: self.path.clone(),
-            rotation: self.rotation.add(rotation),
-            flip_x: false,
-            flip_y: false,
+            rotation: self.rotation + rotation,
+            ..self.clone()
         }
     }
+}
+
+/// Turns a UV region a quarter clockwise around the center of the texture, keeping the
+/// direction of each axis so a mirrored region stays mirrored.
+fn turn_uv_clockwise([u1, v1, u2, v2]: [f32; 4]) -> [f32; 4] {
+    let (nu1, nu2) = same_direction((16.0 - v1, 16.0 - v2), (u1, u2));
+    let (nv1, nv2) = same_direction((u1, u2), (v1, v2));
+    [nu1, nv1, nu2, nv2]
+}
+
+/// Swaps `(a, b)` if it runs the opposite way of `like`.
+fn same_direction((a, b): (f32, f32), like: (f32, f32)) -> (f32, f32) {
+    if (b - a) * (like.1 - like.0) < 0.0 {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+impl FromStr for FaceTexture {
+    type Err = String;
+
+    /// Parses the face string written by [`FaceTexture`]'s `Display`, like
+    /// `minecraft:block/oak_log#r=90#fx`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_face_texture(s)
+    }
+}
 
-    pub fn to_string(&self) -> String {
+impl fmt::Display for FaceTexture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut suffix = String::new();
         if self.rotation != Rotation::CCW0 {
             suffix.push(default_variant_tags::SEP);
@@ -348,40 +653,79 @@ impl FaceTexture {
             suffix.push(default_variant_tags::SEP);
             suffix.push_str(default_variant_tags::FLIP_Y_TEX);
         }
-
-        if suffix.is_empty() {
-            self.path.clone()
-        } else {
-            format!("{}{}", self.path, suffix)
+        if let Some(uv) = self.uv {
+            suffix.push(default_variant_tags::SEP);
+            suffix.push_str(default_variant_tags::UV_TEX);
+            suffix.push_str(&uv.map(|c| c.to_string()).join(","));
         }
+        if let Some(tint) = self.tint {
+            suffix.push(default_variant_tags::SEP);
+            suffix.push_str(default_variant_tags::TINT_TEX);
+            suffix.push_str(&tint.to_hex());
+        }
+
+        write!(f, "{}{}", self.path, suffix)
     }
 }
 
+/// Face textures are oriented as the game maps textures onto the faces of a full cube
+/// element with default UVs, and rotated counter-clockwise as seen from outside the block,
+/// the opposite way of the `rotation` of a model face.
+///
+/// The `rotate_*` methods turn the block counter-clockwise around an axis, see
+/// [`CubeRotation`]. With `uvlock` the textures stay aligned to the world as with
+/// `"uvlock": true`, so faces move but keep their orientation.
 impl BlockTexture {
-    pub fn rotate_x(self, rot: Rotation) -> Self {
+    /// Faces in `x nx y ny z nz` order.
+    pub fn faces(&self) -> [&FaceTexture; 6] {
+        [&self.x, &self.nx, &self.y, &self.ny, &self.z, &self.nz]
+    }
+
+    pub fn map_faces(&self, f: impl Fn(&FaceTexture) -> FaceTexture) -> Self {
+        Self {
+            x: f(&self.x),
+            nx: f(&self.nx),
+            y: f(&self.y),
+            ny: f(&self.ny),
+            z: f(&self.z),
+            nz: f(&self.nz),
+        }
+    }
+
+    /// Turns the block by any of the 24 rotations, see [`CubeRotation`].
+    pub fn rotate(self, rotation: CubeRotation, uvlock: bool) -> Self {
+        let [x, y, z] = rotation.to_xyz();
+        self.rotate_x(x, uvlock)
+            .rotate_y(y, uvlock)
+            .rotate_z(z, uvlock)
+    }
+
+    pub fn rotate_x(self, rot: Rotation, uvlock: bool) -> Self {
         let mut t = self;
 
         for _ in 0..(rot.degrees() / 90) {
+            // Up turns to south
             t = Self {
-                ny: t.z,
-                y: t.nz,
-                z: t.y,
-                nz: t.ny,
-                nx: t.nx.add_rotation(Rotation::CCW270),
-                x: t.x.add_rotation(Rotation::CCW90),
+                y: t.z.turn(Rotation::CCW180, uvlock),
+                nz: t.y,
+                ny: t.nz,
+                z: t.ny.turn(Rotation::CCW180, uvlock),
+                nx: t.nx.turn(Rotation::CCW270, uvlock),
+                x: t.x.turn(Rotation::CCW90, uvlock),
             };
         }
 
         t
     }
 
-    pub fn rotate_y(self, rot: Rotation) -> Self {
+    pub fn rotate_y(self, rot: Rotation, uvlock: bool) -> Self {
         let mut t = self;
 
         for _ in 0..(rot.degrees() / 90) {
+            // North turns to west
             t = Self {
-                ny: t.ny.add_rotation(Rotation::CCW270),
-                y: t.y.add_rotation(Rotation::CCW90),
+                ny: t.ny.turn(Rotation::CCW270, uvlock),
+                y: t.y.turn(Rotation::CCW90, uvlock),
                 z: t.x,
                 nz: t.nx,
                 nx: t.z,
@@ -392,17 +736,18 @@ impl BlockTexture {
         t
     }
 
-    pub fn rotate_z(self, rot: Rotation) -> Self {
+    pub fn rotate_z(self, rot: Rotation, uvlock: bool) -> Self {
         let mut t = self;
 
         for _ in 0..(rot.degrees() / 90) {
+            // Up turns to west
             t = Self {
-                ny: t.nx,
-                y: t.x,
-                z: t.z.add_rotation(Rotation::CCW90),
-                nz: t.nz.add_rotation(Rotation::CCW270),
-                nx: t.y,
-                x: t.ny,
+                ny: t.nx.turn(Rotation::CCW90, uvlock),
+                y: t.x.turn(Rotation::CCW90, uvlock),
+                z: t.z.turn(Rotation::CCW270, uvlock),
+                nz: t.nz.turn(Rotation::CCW90, uvlock),
+                nx: t.y.turn(Rotation::CCW90, uvlock),
+                x: t.ny.turn(Rotation::CCW90, uvlock),
             };
         }
 
@@ -410,7 +755,7 @@ impl BlockTexture {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BlockVolume {
     pub path: String,
     #[serde(skip_serializing_if = "is_rotation_zero", default)]
@@ -425,6 +770,9 @@ pub struct BlockVolume {
     pub flip_y: bool,
     #[serde(skip_serializing_if = "is_false", default)]
     pub flip_z: bool,
+    /// Color multiplied into the voxel faces sampled from faces with a `tintindex`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tint: Option<Color>,
 }
 
 fn is_rotation_zero(r: &Rotation) -> bool {
@@ -435,7 +783,11 @@ fn is_false(b: &bool) -> bool {
     !b
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+/// Turn by a multiple of 90 degrees, counter-clockwise.
+///
+/// Face textures turn counter-clockwise as seen on the face. Blocks and volumes turn
+/// counter-clockwise around each axis looking from its positive end, see [`CubeRotation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
 #[serde(into = "i32", try_from = "i32")]
 pub enum Rotation {
     #[default]
@@ -446,6 +798,13 @@ pub enum Rotation {
 }
 
 impl Rotation {
+    pub const ALL: [Rotation; 4] = [
+        Rotation::CCW0,
+        Rotation::CCW90,
+        Rotation::CCW180,
+        Rotation::CCW270,
+    ];
+
     pub fn degrees(self) -> i32 {
         match self {
             Rotation::CCW0 => 0,
@@ -455,6 +814,12 @@ impl Rotation {
         }
     }
 
+    /// Rotation by `deg` taken modulo 360, like `-90` or `450`, `None` for angles that are
+    /// not a multiple of 90.
+    pub fn normalized(deg: i32) -> Option<Self> {
+        Self::from_degrees(deg.rem_euclid(360))
+    }
+
     pub fn from_degrees(deg: i32) -> Option<Self> {
         match deg {
             0 => Some(Rotation::CCW0),
@@ -464,12 +829,25 @@ impl Rotation {
             _ => None,
         }
     }
+}
+
+impl ops::Add for Rotation {
+    type Output = Self;
 
-    pub fn add(self, rotation: Self) -> Self {
+    fn add(self, rotation: Self) -> Self {
         Self::from_degrees((self.degrees() + rotation.degrees()) % 360).unwrap()
     }
 }
 
+impl ops::Neg for Rotation {
+    type Output = Self;
+
+    /// The same turn the other way.
+    fn neg(self) -> Self {
+        Self::from_degrees((360 - self.degrees()) % 360).unwrap()
+    }
+}
+
 impl From<Rotation> for i32 {
     fn from(rotation: Rotation) -> Self {
         rotation.degrees()
@@ -484,10 +862,87 @@ impl TryFrom<i32> for Rotation {
     }
 }
 
+/// One of the 24 ways to turn a block by quarter turns.
+///
+/// Rotations around `x`, `y` and `z` turn counter-clockwise looking from the positive end
+/// of the axis: up turns to south, north turns to west and up turns to west, respectively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CubeRotation {
+    /// Maps block coordinates relative to the block center, `x` east, `y` up, `z` south
+    matrix: [[i8; 3]; 3],
+}
+
+impl CubeRotation {
+    pub const IDENTITY: Self = Self {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+    const X: Self = Self {
+        matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+    };
+    const Y: Self = Self {
+        matrix: [[0, 0, 1], [0, 1, 0], [-1, 0, 0]],
+    };
+    const Z: Self = Self {
+        matrix: [[0, -1, 0], [1, 0, 0], [0, 0, 1]],
+    };
+
+    /// Turns around `x`, then `y`, then `z`, in the order a blockstate variant turns its
+    /// model, see [`crate::textures::model_rotation`].
+    pub fn from_xyz(x: Rotation, y: Rotation, z: Rotation) -> Self {
+        [(Self::X, x), (Self::Y, y), (Self::Z, z)]
+            .into_iter()
+            .fold(Self::IDENTITY, |r, (axis, rot)| {
+                (0..rot.degrees() / 90).fold(r, |r, _| r.then(axis))
+            })
+    }
+
+    /// Turns around `x`, `y` and `z` that make up this rotation, see [`Self::from_xyz`].
+    pub fn to_xyz(self) -> [Rotation; 3] {
+        Rotation::ALL
+            .into_iter()
+            .flat_map(|z| Rotation::ALL.map(|y| (y, z)))
+            .flat_map(|(y, z)| Rotation::ALL.map(|x| [x, y, z]))
+            .find(|&[x, y, z]| Self::from_xyz(x, y, z) == self)
+            .expect("Quarter turns around x, y and z reach every rotation")
+    }
+
+    /// `self` followed by `other`.
+    pub fn then(self, other: Self) -> Self {
+        let (a, b) = (self.matrix, other.matrix);
+        Self {
+            matrix: [0, 1, 2].map(|i| [0, 1, 2].map(|j| (0..3).map(|k| b[i][k] * a[k][j]).sum())),
+        }
+    }
+
+    /// The rotation that turns the block back.
+    pub fn inverse(self) -> Self {
+        let m = self.matrix;
+        Self {
+            matrix: [0, 1, 2].map(|i| [0, 1, 2].map(|j| m[j][i])),
+        }
+    }
+
+    /// All 24 rotations, the identity first.
+    pub fn all() -> Vec<Self> {
+        let mut all: Vec<Self> = Vec::with_capacity(24);
+        for z in Rotation::ALL {
+            for y in Rotation::ALL {
+                for x in Rotation::ALL {
+                    let r = Self::from_xyz(x, y, z);
+                    if !all.contains(&r) {
+                        all.push(r);
+                    }
+                }
+            }
+        }
+        all
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Group {
-    block_ids: BlockIds,
-    rule: GroupRule,
+    pub block_ids: BlockIds,
+    pub rule: GroupRule,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -497,13 +952,70 @@ pub enum BlockIds {
     VariantSet(String),
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Debug, PartialEq)]
 pub enum GroupRule {
-    RandomChoice,
+    RandomChoice {
+        /// Relative weight per block id, 1 if missing
+        weights: BTreeMap<String, u32>,
+    },
     Custom(serde_json::Value),
 }
 
+impl Serialize for GroupRule {
+    /// Writes equal weights as the unit `"random_choice"`, which readers without weights
+    /// understand too.
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "snake_case")]
+        enum Rule<'a> {
+            RandomChoice { weights: &'a BTreeMap<String, u32> },
+            Custom(&'a serde_json::Value),
+        }
+
+        match self {
+            GroupRule::RandomChoice { weights } if weights.is_empty() => {
+                s.serialize_str("random_choice")
+            }
+            GroupRule::RandomChoice { weights } => Rule::RandomChoice { weights }.serialize(s),
+            GroupRule::Custom(value) => Rule::Custom(value).serialize(s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GroupRule {
+    /// Also reads the unit `"random_choice"` palettes were written with before weights, as
+    /// equal weights.
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum Rule {
+            RandomChoice {
+                #[serde(default)]
+                weights: BTreeMap<String, u32>,
+            },
+            Custom(serde_json::Value),
+        }
+
+        let value = serde_json::Value::deserialize(d)?;
+        if value.as_str() == Some("random_choice") {
+            return Ok(GroupRule::RandomChoice {
+                weights: BTreeMap::new(),
+            });
+        }
+
+        match Rule::deserialize(value).map_err(serde::de::Error::custom)? {
+            Rule::RandomChoice { weights } => Ok(GroupRule::RandomChoice { weights }),
+            Rule::Custom(value) => Ok(GroupRule::Custom(value)),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct VariantSet {
     input_block_ids: BlockIds,
@@ -515,7 +1027,7 @@ pub struct VariantSet {
     custom: Option<serde_json::Value>,
 }
 
-mod default_variant_tags {
+pub mod default_variant_tags {
     pub const SEP: char = '#';
 
     pub const ROT_X: &str = "rx=";
@@ -529,9 +1041,13 @@ mod default_variant_tags {
     pub const ROT_TEX: &str = "r=";
     pub const FLIP_X_TEX: &str = "fx";
     pub const FLIP_Y_TEX: &str = "fy";
+    /// Followed by `u1,v1,u2,v2` in pixels
+    pub const UV_TEX: &str = "uv=";
+    /// Followed by the color as `rrggbbaa` in hex
+    pub const TINT_TEX: &str = "tint=";
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(into = "String", try_from = "String")]
 pub struct Color {
     pub r: u8,
@@ -540,20 +1056,23 @@ pub struct Color {
     pub a: u8,
 }
 
-impl From<Color> for String {
-    fn from(color: Color) -> Self {
-        format!(
-            "#{:02x}{:02x}{:02x}{:02x}",
-            color.r, color.g, color.b, color.a
-        )
+impl Color {
+    /// Opaque color from `0xrrggbb`.
+    pub const fn rgb(rgb: u32) -> Self {
+        Color {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+            a: 255,
+        }
     }
-}
 
-impl TryFrom<String> for Color {
-    type Error = String;
+    /// The color as `rrggbbaa` in hex.
+    fn to_hex(self) -> String {
+        format!("{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
 
-    fn try_from(s: String) -> Result<Self, Self::Error> {
-        let s = s.strip_prefix('#').ok_or("Color must start with #")?;
+    fn from_hex(s: &str) -> Result<Self, String> {
         if s.len() != 8 {
             return Err(format!("Color must be 8 hex digits, got {}", s.len()));
         }
@@ -568,3 +1087,18 @@ impl TryFrom<String> for Color {
         })
     }
 }
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        format!("#{}", color.to_hex())
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let s = s.strip_prefix('#').ok_or("Color must start with #")?;
+        Color::from_hex(s)
+    }
+}
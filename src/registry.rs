@@ -0,0 +1,88 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, schema::location::ResourceLocation, variants::state_combinations};
+
+/// Properties and default state of every registered block.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BlockRegistry {
+    pub blocks: IndexMap<ResourceLocation, BlockDefinition>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BlockDefinition {
+    /// Possible values of each property, in the order the game enumerates them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub default_state: BTreeMap<String, String>,
+}
+
+/// Entry of the `reports/blocks.json` written by the game's data generator.
+#[derive(Deserialize)]
+struct ReportBlock {
+    #[serde(default)]
+    properties: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    states: Vec<ReportState>,
+}
+
+#[derive(Deserialize)]
+struct ReportState {
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+    #[serde(default)]
+    default: bool,
+}
+
+impl BlockRegistry {
+    /// Reads the block report produced by the data generator
+    /// (`java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports`).
+    pub fn load_blocks_report<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path_str = path.as_ref().display().to_string();
+        let json = fs::read_to_string(&path).map_err(|source| Error::Io {
+            path: path_str.clone(),
+            source,
+        })?;
+        let report: IndexMap<ResourceLocation, ReportBlock> =
+            serde_json::from_str(&json).map_err(|e| Error::json(&path_str, e))?;
+
+        let blocks = report
+            .into_iter()
+            .map(|(name, block)| {
+                let default_state = block
+                    .states
+                    .into_iter()
+                    .find(|s| s.default)
+                    .map(|s| s.properties)
+                    .unwrap_or_default();
+
+                let definition = BlockDefinition {
+                    properties: block.properties,
+                    default_state,
+                };
+                (name, definition)
+            })
+            .collect();
+
+        Ok(Self { blocks })
+    }
+}
+
+impl BlockDefinition {
+    /// Every state of the block as a blockstate key such as `axis=x`.
+    pub fn states(&self) -> Vec<String> {
+        state_combinations(&self.properties)
+    }
+
+    /// Blockstate key of the state the game places by default, empty without properties.
+    pub fn default_state_key(&self) -> String {
+        self.default_state
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
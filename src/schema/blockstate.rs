@@ -1,13 +1,17 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
-use std::fs;
 use std::io;
-use std::path::Path;
+
+use crate::error::{Diagnostics, Error};
+use crate::schema::location::ResourceLocation;
+use crate::source::{self, AssetSource};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BlockState {
-    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
-    pub variants: BTreeMap<String, ModelVariant>,
+    /// In file order, which decides the variant a state picks
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub variants: IndexMap<String, ModelVariant>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub multipart: Vec<MultipartCase>,
 }
@@ -21,7 +25,7 @@ pub enum ModelVariant {
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ModelDefinition {
-    pub model: String,
+    pub model: ResourceLocation,
     #[serde(default, skip_serializing_if = "is_zero")]
     pub x: i32,
     #[serde(default, skip_serializing_if = "is_zero")]
@@ -83,6 +87,24 @@ impl PropertyMatch {
     }
 }
 
+impl MultipartCondition {
+    pub fn matches(&self, state: &BTreeMap<String, String>) -> bool {
+        match self {
+            MultipartCondition::Or { or } => or.iter().any(|p| p.matches(state)),
+            MultipartCondition::And { and } => and.iter().all(|p| p.matches(state)),
+            MultipartCondition::Properties(property_match) => property_match.matches(state),
+        }
+    }
+}
+
+/// Parses a blockstate key such as `facing=north,half=top` into its properties.
+pub fn parse_state(key: &str) -> BTreeMap<String, String> {
+    key.split(',')
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect()
+}
+
 fn is_zero(value: &i32) -> bool {
     *value == 0
 }
@@ -107,6 +129,40 @@ impl BlockState {
     pub fn is_multipart(&self) -> bool {
         !self.multipart.is_empty()
     }
+
+    /// Key of the variant the game picks for `state`: the first one in the file whose
+    /// properties all match.
+    ///
+    /// Always `None` for multipart blockstates.
+    pub fn variant_key(&self, state: &BTreeMap<String, String>) -> Option<&str> {
+        self.variants
+            .keys()
+            .find(|key| {
+                parse_state(key)
+                    .iter()
+                    .all(|(k, v)| state.get(k) == Some(v))
+            })
+            .map(String::as_str)
+    }
+
+    /// Models the game renders for `state`.
+    ///
+    /// Each entry is one part of the block (a single variant, or one active multipart case),
+    /// which may itself be a weighted choice between several models.
+    pub fn applied_models(&self, state: &BTreeMap<String, String>) -> Vec<&ModelVariant> {
+        if self.is_variants() {
+            return self
+                .variant_key(state)
+                .map(|key| vec![&self.variants[key]])
+                .unwrap_or_default();
+        }
+
+        self.multipart
+            .iter()
+            .filter(|case| case.when.as_ref().is_none_or(|when| when.matches(state)))
+            .map(|case| &case.apply)
+            .collect()
+    }
 }
 
 impl ModelVariant {
@@ -118,38 +174,52 @@ impl ModelVariant {
     }
 }
 
-pub fn load_all<P: AsRef<Path>>(dir: P) -> io::Result<HashMap<String, BlockState>> {
+/// Loads `assets/<namespace>/blockstates/*.json` for every namespace in `source`.
+///
+/// Files that cannot be read or parsed are skipped and reported to `diagnostics`. Each block's
+/// file is read whole from the topmost layer that has it, so a resource pack overriding a
+/// blockstate has to list all of its variants.
+pub fn load_all(
+    source: &dyn AssetSource,
+    diagnostics: &mut Diagnostics,
+) -> io::Result<HashMap<ResourceLocation, BlockState>> {
     let mut blockstates = HashMap::new();
 
-    let entries = fs::read_dir(dir)?;
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
-        }
-
-        let block_name = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(name) => name.to_string(),
-            None => continue,
+    for namespace in source::namespaces(source)? {
+        let paths = match source.list(&format!("assets/{}/blockstates", namespace)) {
+            Ok(p) => p,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
         };
 
-        let content = match fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let content = content
-            .replace("minecraft:block/", "")
-            .replace("block/", "");
+        for path in paths {
+            let Some(file_name) = path.rsplit('/').next() else {
+                continue;
+            };
+
+            let block_name = match file_name.strip_suffix(".json") {
+                Some(name) => ResourceLocation::new(namespace.as_str(), name),
+                None => continue,
+            };
+
+            let content = match source.read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    diagnostics.push(Error::Io { path, source: e });
+                    continue;
+                }
+            };
 
-        let blockstate: BlockState = match serde_json::from_str(&content) {
-            Ok(bs) => bs,
-            Err(_) => continue,
-        };
+            let blockstate: BlockState = match serde_json::from_str(&content) {
+                Ok(bs) => bs,
+                Err(e) => {
+                    diagnostics.push(Error::json(&path, e));
+                    continue;
+                }
+            };
 
-        blockstates.insert(block_name, blockstate);
+            blockstates.insert(block_name, blockstate);
+        }
     }
 
     Ok(blockstates)
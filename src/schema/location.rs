@@ -0,0 +1,83 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// Namespaced asset id such as `minecraft:block/stone`.
+///
+/// A missing namespace means `minecraft`, as in the game.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct ResourceLocation {
+    pub namespace: String,
+    pub path: String,
+}
+
+impl ResourceLocation {
+    pub fn new(namespace: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            path: path.into(),
+        }
+    }
+
+    pub fn minecraft(path: impl Into<String>) -> Self {
+        Self::new(DEFAULT_NAMESPACE, path)
+    }
+
+    /// Path of the file this location refers to inside a resource root, e.g.
+    /// `assets/minecraft/textures/block/stone.png` for `asset_path("textures", "png")`.
+    pub fn asset_path(&self, kind: &str, extension: &str) -> String {
+        format!(
+            "assets/{}/{}/{}.{}",
+            self.namespace, kind, self.path, extension
+        )
+    }
+
+    /// `namespace/path`, for laying out files in output directories.
+    pub fn file_stem(&self) -> String {
+        format!("{}/{}", self.namespace, self.path)
+    }
+}
+
+impl fmt::Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+impl FromStr for ResourceLocation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, path) = s.split_once(':').unwrap_or((DEFAULT_NAMESPACE, s));
+
+        if namespace.is_empty() || !namespace.chars().all(is_namespace_char) {
+            return Err(format!("Invalid namespace in resource location {:?}", s));
+        }
+        if path.is_empty() || !path.chars().all(|c| c == '/' || is_namespace_char(c)) {
+            return Err(format!("Invalid path in resource location {:?}", s));
+        }
+
+        Ok(Self::new(namespace, path))
+    }
+}
+
+fn is_namespace_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.')
+}
+
+impl From<ResourceLocation> for String {
+    fn from(location: ResourceLocation) -> Self {
+        location.to_string()
+    }
+}
+
+impl TryFrom<String> for ResourceLocation {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// Contents of a `.png.mcmeta` file next to a texture.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TextureMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationMeta>,
+}
+
+/// Animation of a texture whose image holds its frames side by side or stacked.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AnimationMeta {
+    /// Ticks each frame shows unless the frame says otherwise
+    #[serde(default = "default_frametime")]
+    pub frametime: u32,
+
+    /// Blend each frame into the next over its time instead of switching at once
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub interpolate: bool,
+
+    /// Frame order, every frame of the image once in order if `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frames: Option<Vec<AnimationFrame>>,
+
+    /// Frame size in pixels. A side left unset spans the image, and with neither set frames
+    /// are square and as wide as the image is narrow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum AnimationFrame {
+    Index(u32),
+    Timed {
+        index: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        time: Option<u32>,
+    },
+}
+
+impl AnimationFrame {
+    pub fn index(&self) -> u32 {
+        match self {
+            AnimationFrame::Index(index) | AnimationFrame::Timed { index, .. } => *index,
+        }
+    }
+
+    /// Ticks the frame shows, `frametime` unless the frame has its own time.
+    pub fn time(&self, frametime: u32) -> u32 {
+        match self {
+            AnimationFrame::Timed {
+                time: Some(time), ..
+            } => *time,
+            _ => frametime,
+        }
+    }
+}
+
+fn default_frametime() -> u32 {
+    1
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
@@ -1,2 +1,4 @@
 pub mod blockstate;
+pub mod location;
+pub mod mcmeta;
 pub mod model;
@@ -1,22 +1,25 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::io;
-use std::path::Path;
+
+use crate::error::{Diagnostics, Error};
+use crate::schema::location::ResourceLocation;
+use crate::source::{self, AssetSource};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Model {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub parent: Option<String>,
+    pub parent: Option<ResourceLocation>,
 
-    #[serde(default = "default_true", skip_serializing_if = "is_true")]
-    pub ambientocclusion: bool,
+    /// Inherited from the parent when unset, see [`crate::baked::BakedModel`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ambientocclusion: Option<bool>,
 
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub display: HashMap<String, DisplayTransform>,
 
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub textures: HashMap<String, String>,
+    pub textures: HashMap<String, TextureRef>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub elements: Vec<Element>,
@@ -78,7 +81,7 @@ pub struct Face {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uv: Option<[f32; 4]>,
 
-    pub texture: String,
+    pub texture: TextureRef,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cullface: Option<String>,
@@ -90,6 +93,34 @@ pub struct Face {
     pub tintindex: Option<i32>,
 }
 
+/// Texture of a model face or texture variable: either `#variable` or a texture location.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum TextureRef {
+    Variable(String),
+    Location(ResourceLocation),
+}
+
+impl From<TextureRef> for String {
+    fn from(texture: TextureRef) -> Self {
+        match texture {
+            TextureRef::Variable(var) => format!("#{}", var),
+            TextureRef::Location(location) => location.to_string(),
+        }
+    }
+}
+
+impl TryFrom<String> for TextureRef {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.strip_prefix('#') {
+            Some(var) => Ok(TextureRef::Variable(var.to_owned())),
+            None => Ok(TextureRef::Location(s.parse()?)),
+        }
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -106,38 +137,51 @@ fn is_zero_i32(value: &i32) -> bool {
     *value == 0
 }
 
-pub fn load_all<P: AsRef<Path>>(dir: P) -> io::Result<HashMap<String, Model>> {
+/// Loads `assets/<namespace>/models/**/*.json` for every namespace in `source`.
+///
+/// Files that cannot be read or parsed are skipped and reported to `diagnostics`.
+pub fn load_all(
+    source: &dyn AssetSource,
+    diagnostics: &mut Diagnostics,
+) -> io::Result<HashMap<ResourceLocation, Model>> {
     let mut models = HashMap::new();
 
-    let entries = fs::read_dir(dir)?;
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
-        }
+    for namespace in source::namespaces(source)? {
+        let models_dir = format!("assets/{}/models/", namespace);
 
-        let model_name = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(name) => name.to_string(),
-            None => continue,
+        let paths = match source::walk(source, &models_dir) {
+            Ok(p) => p,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
         };
 
-        let content = match fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let content = content
-            .replace("minecraft:block/", "")
-            .replace("block/", "");
-
-        let model: Model = match serde_json::from_str(&content) {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
-
-        models.insert(model_name, model);
+        for path in paths {
+            let model_name = match path
+                .strip_prefix(&models_dir)
+                .and_then(|p| p.strip_suffix(".json"))
+            {
+                Some(name) => ResourceLocation::new(namespace.as_str(), name),
+                None => continue,
+            };
+
+            let content = match source.read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    diagnostics.push(Error::Io { path, source: e });
+                    continue;
+                }
+            };
+
+            let model: Model = match serde_json::from_str(&content) {
+                Ok(m) => m,
+                Err(e) => {
+                    diagnostics.push(Error::json(&path, e));
+                    continue;
+                }
+            };
+
+            models.insert(model_name, model);
+        }
     }
 
     Ok(models)
@@ -0,0 +1,136 @@
+//! Sponge schematics (`.schem`, format version 2) of block art, which WorldEdit and most
+//! world editors can paste.
+//!
+//! A schematic is a gzipped NBT compound listing the block states in a palette and the
+//! palette index of every block, x fastest, then z, then y.
+
+use std::io::{self, Write};
+
+use flate2::{Compression, write::GzEncoder};
+use indexmap::IndexMap;
+
+use crate::art::BlockArt;
+
+/// Data version of Minecraft 1.21.5, the version schematics are written for by default.
+pub const DATA_VERSION: i32 = 4325;
+
+const AIR: &str = "minecraft:air";
+
+const TAG_END: u8 = 0;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+
+/// Writes `art` as a schematic for the game version of `data_version`, with air where the
+/// art has no block.
+pub fn write_schematic(art: &BlockArt, data_version: i32, writer: impl Write) -> io::Result<()> {
+    let [width, height, length] = art.facing.size(art.width, art.height);
+    let [width_tag, height_tag, length_tag] = [width, height, length].map(|s| {
+        u16::try_from(s).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} blocks do not fit in a schematic", s),
+            )
+        })
+    });
+
+    let mut palette: IndexMap<String, i32> = IndexMap::new();
+    palette.insert(AIR.to_owned(), 0);
+    let mut blocks = vec![0; width * height * length];
+    for (row, cells) in art.rows.iter().enumerate() {
+        for (column, id) in cells.iter().enumerate() {
+            let Some(id) = id else {
+                continue;
+            };
+            let next = palette.len() as i32;
+            let index = *palette.entry(block_state(id)).or_insert(next);
+            let [x, y, z] = art.facing.position(column, row, art.width, art.height);
+            blocks[x + z * width + y * width * length] = index;
+        }
+    }
+
+    // Palette indices as unsigned LEB128 varints
+    let mut block_data = Vec::with_capacity(blocks.len());
+    for index in blocks {
+        let mut value = index as u32;
+        while value >= 0x80 {
+            block_data.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        block_data.push(value as u8);
+    }
+
+    let mut nbt = Nbt(GzEncoder::new(writer, Compression::default()));
+    nbt.tag(TAG_COMPOUND, "Schematic")?;
+    nbt.int("Version", 2)?;
+    nbt.int("DataVersion", data_version)?;
+    nbt.short("Width", width_tag?)?;
+    nbt.short("Height", height_tag?)?;
+    nbt.short("Length", length_tag?)?;
+    nbt.int_array("Offset", &[0, 0, 0])?;
+    nbt.int("PaletteMax", palette.len() as i32)?;
+    nbt.tag(TAG_COMPOUND, "Palette")?;
+    for (state, index) in &palette {
+        nbt.int(state, *index)?;
+    }
+    nbt.end()?;
+    nbt.byte_array("BlockData", &block_data)?;
+    nbt.end()?;
+    nbt.0.finish()?;
+    Ok(())
+}
+
+/// Block state a material places, like `minecraft:oak_log[axis=x]` for the material
+/// `minecraft:oak_log#axis=x`. Weighted alternatives `<id>@<index>` place the block of `<id>`.
+pub fn block_state(material_id: &str) -> String {
+    let id = material_id
+        .rsplit_once('@')
+        .map_or(material_id, |(block, _)| block);
+    match id.split_once('#') {
+        Some((block, "")) => block.to_owned(),
+        Some((block, state)) => format!("{}[{}]", block, state),
+        None => id.to_owned(),
+    }
+}
+
+/// Big-endian NBT tags written one after another.
+struct Nbt<W: Write>(W);
+
+impl<W: Write> Nbt<W> {
+    fn tag(&mut self, tag: u8, name: &str) -> io::Result<()> {
+        self.0.write_all(&[tag])?;
+        self.0.write_all(&(name.len() as u16).to_be_bytes())?;
+        self.0.write_all(name.as_bytes())
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        self.0.write_all(&[TAG_END])
+    }
+
+    fn short(&mut self, name: &str, value: u16) -> io::Result<()> {
+        self.tag(TAG_SHORT, name)?;
+        self.0.write_all(&value.to_be_bytes())
+    }
+
+    fn int(&mut self, name: &str, value: i32) -> io::Result<()> {
+        self.tag(TAG_INT, name)?;
+        self.0.write_all(&value.to_be_bytes())
+    }
+
+    fn byte_array(&mut self, name: &str, values: &[u8]) -> io::Result<()> {
+        self.tag(TAG_BYTE_ARRAY, name)?;
+        self.0.write_all(&(values.len() as i32).to_be_bytes())?;
+        self.0.write_all(values)
+    }
+
+    fn int_array(&mut self, name: &str, values: &[i32]) -> io::Result<()> {
+        self.tag(TAG_INT_ARRAY, name)?;
+        self.0.write_all(&(values.len() as i32).to_be_bytes())?;
+        for value in values {
+            self.0.write_all(&value.to_be_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use serde::Serialize;
+
+use crate::{
+    palette::Rotation,
+    schema::model::{Axis, Element, ElementRotation},
+};
+
+/// Pixel coordinates are compared with this tolerance, as packs often use values like 0.001.
+const EPSILON: f32 = 0.01;
+
+/// Approximate shape of a block state, for mapping it to blocks of a coarser palette.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Shape {
+    /// No elements at all, like air or blocks rendered by a block entity
+    Empty,
+    FullCube,
+    BottomSlab,
+    TopSlab,
+    Stair,
+    /// A full-width layer resting on the ground, like carpets and snow, `height` in pixels
+    Layer {
+        height: f32,
+    },
+    /// Thin full-height elements, like glass panes and iron bars
+    Pane,
+    /// Planes crossed diagonally, like most plants
+    Cross,
+    Other,
+}
+
+/// Axis aligned box in pixels, `0..16` spanning the whole block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cuboid {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+}
+
+impl Cuboid {
+    pub fn of(element: &Element) -> Self {
+        Self {
+            from: element.from,
+            to: element.to,
+        }
+    }
+
+    /// Rotates the box around the block center, around x, then y, then z, see
+    /// [`crate::palette::CubeRotation`].
+    pub fn rotated(self, [x, y, z]: [Rotation; 3]) -> Self {
+        let mut c = self;
+
+        for _ in 0..x.degrees() / 90 {
+            // Up turns to south
+            c = c.map(|[x, y, z]| [x, 16.0 - z, y]);
+        }
+        for _ in 0..y.degrees() / 90 {
+            // North turns to west
+            c = c.map(|[x, y, z]| [z, y, 16.0 - x]);
+        }
+        for _ in 0..z.degrees() / 90 {
+            // Up turns to west
+            c = c.map(|[x, y, z]| [16.0 - y, x, z]);
+        }
+
+        c
+    }
+
+    fn map(self, f: impl Fn([f32; 3]) -> [f32; 3]) -> Self {
+        let (a, b) = (f(self.from), f(self.to));
+
+        Self {
+            from: [0, 1, 2].map(|i| a[i].min(b[i])),
+            to: [0, 1, 2].map(|i| a[i].max(b[i])),
+        }
+    }
+
+    fn size(&self, axis: usize) -> f32 {
+        self.to[axis] - self.from[axis]
+    }
+
+    fn spans(&self, axis: usize, from: f32, to: f32) -> bool {
+        near(self.from[axis], from) && near(self.to[axis], to)
+    }
+
+    fn is_full(&self) -> bool {
+        (0..3).all(|axis| self.spans(axis, 0.0, 16.0))
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        Self {
+            from: [0, 1, 2].map(|i| self.from[i].min(other.from[i])),
+            to: [0, 1, 2].map(|i| self.to[i].max(other.to[i])),
+        }
+    }
+}
+
+/// Classifies the elements of all models applied for a block state.
+///
+/// Each element is given as its box in block space together with its own rotation, if any.
+pub fn classify(elements: &[(Cuboid, Option<&ElementRotation>)]) -> Shape {
+    if elements.is_empty() {
+        return Shape::Empty;
+    }
+
+    let tilted = |r: &Option<&ElementRotation>| r.is_some_and(|r| !near(r.angle, 0.0));
+    if elements
+        .iter()
+        .all(|(_, r)| r.is_some_and(|r| matches!(r.axis, Axis::Y) && near(r.angle.abs(), 45.0)))
+    {
+        return Shape::Cross;
+    }
+    if elements.iter().any(|(_, r)| tilted(r)) {
+        return Shape::Other;
+    }
+
+    let cuboids: Vec<Cuboid> = elements.iter().map(|(c, _)| *c).collect();
+    let bounds = cuboids[1..]
+        .iter()
+        .fold(cuboids[0], |bounds, c| bounds.union(c));
+
+    if cuboids.iter().all(Cuboid::is_full) {
+        return Shape::FullCube;
+    }
+
+    let full_width = |c: &Cuboid| c.spans(0, 0.0, 16.0) && c.spans(2, 0.0, 16.0);
+
+    if cuboids.iter().all(full_width) {
+        if near(bounds.from[1], 0.0) && near(bounds.to[1], 8.0) {
+            return Shape::BottomSlab;
+        }
+        if near(bounds.from[1], 8.0) && near(bounds.to[1], 16.0) {
+            return Shape::TopSlab;
+        }
+        if near(bounds.from[1], 0.0) && bounds.to[1] < 16.0 - EPSILON {
+            return Shape::Layer {
+                height: bounds.to[1],
+            };
+        }
+    }
+
+    if bounds.is_full() && (2..=3).contains(&cuboids.len()) && is_stair(&cuboids) {
+        return Shape::Stair;
+    }
+
+    if bounds.spans(1, 0.0, 16.0)
+        && cuboids
+            .iter()
+            .all(|c| c.size(0).min(c.size(2)) <= 2.0 + EPSILON)
+    {
+        return Shape::Pane;
+    }
+
+    Shape::Other
+}
+
+/// A half slab with the remaining elements all in the other half of the block.
+fn is_stair(cuboids: &[Cuboid]) -> bool {
+    [(0.0, 8.0), (8.0, 16.0)].into_iter().any(|(from, to)| {
+        let is_slab =
+            |c: &Cuboid| c.spans(0, 0.0, 16.0) && c.spans(2, 0.0, 16.0) && c.spans(1, from, to);
+        let (other_from, other_to) = (16.0 - to, 16.0 - from);
+
+        cuboids.iter().filter(|c| is_slab(c)).count() == 1
+            && cuboids
+                .iter()
+                .filter(|c| !is_slab(c))
+                .all(|c| c.from[1] >= other_from - EPSILON && c.to[1] <= other_to + EPSILON)
+    })
+}
+
+fn near(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
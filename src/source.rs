@@ -0,0 +1,336 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use zip::{ZipArchive, result::ZipError};
+
+/// Read-only view over a resource root, i.e. the directory that contains `assets/`.
+///
+/// Paths are `/`-separated and relative to the root, e.g. `assets/minecraft/blockstates/stone.json`.
+pub trait AssetSource {
+    /// Paths of all files directly inside `dir`.
+    fn list(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    /// Paths of all directories directly inside `dir`.
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Human readable description used in messages.
+    fn describe(&self) -> String;
+}
+
+/// Opens `path` as a zip/jar archive if it is a file, or as an extracted directory otherwise.
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn AssetSource>> {
+    let path = path.as_ref();
+
+    if path.is_file() {
+        Ok(Box::new(ZipSource::open(path)?))
+    } else if path.is_dir() {
+        Ok(Box::new(DirSource::new(path)))
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Asset source not found: {}", path.display()),
+        ))
+    }
+}
+
+/// Opens each of `paths` with [`open`] and stacks them in order, later paths overriding earlier ones.
+pub fn open_layered<P: AsRef<Path>>(paths: &[P]) -> io::Result<LayeredSource> {
+    let mut layered = LayeredSource::default();
+
+    for path in paths {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_owned();
+
+        layered.push(name, open(path)?);
+    }
+
+    Ok(layered)
+}
+
+/// Paths of all files inside `dir` and its subdirectories.
+pub fn walk(source: &dyn AssetSource, dir: &str) -> io::Result<Vec<String>> {
+    let mut paths = source.list(dir)?;
+
+    for sub_dir in source.list_dirs(dir)? {
+        paths.extend(walk(source, &sub_dir)?);
+    }
+
+    Ok(paths)
+}
+
+/// Namespaces with an `assets/<namespace>` directory in `source`.
+pub fn namespaces(source: &dyn AssetSource) -> io::Result<Vec<String>> {
+    Ok(source
+        .list_dirs("assets")?
+        .into_iter()
+        .filter_map(|d| d.rsplit('/').next().map(str::to_owned))
+        .collect())
+}
+
+/// Joins a `/`-separated directory and a relative path.
+pub fn join(dir: &str, path: &str) -> String {
+    let dir = dir.trim_end_matches('/');
+
+    if dir.is_empty() {
+        path.to_owned()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl AssetSource for DirSource {
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let dir = dir.trim_end_matches('/');
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(self.root.join(dir))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            if let Some(name) = entry.file_name().to_str() {
+                paths.push(join(dir, name));
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        let dir = dir.trim_end_matches('/');
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(self.root.join(dir))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+
+            if let Some(name) = entry.file_name().to_str() {
+                paths.push(join(dir, name));
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.root.join(path))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+}
+
+/// Client `.jar` or resource pack `.zip`.
+pub struct ZipSource {
+    path: PathBuf,
+    names: Vec<String>,
+    archive: Mutex<ZipArchive<BufReader<File>>>,
+}
+
+impl ZipSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let archive = ZipArchive::new(BufReader::new(File::open(path)?)).map_err(zip_error)?;
+
+        let mut names: Vec<String> = archive
+            .file_names()
+            .filter(|n| !n.ends_with('/'))
+            .map(str::to_owned)
+            .collect();
+        names.sort();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            names,
+            archive: Mutex::new(archive),
+        })
+    }
+}
+
+impl AssetSource for ZipSource {
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = join(dir, "");
+
+        Ok(self
+            .names
+            .iter()
+            .filter(|n| {
+                n.strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains('/'))
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = join(dir, "");
+
+        let dirs: BTreeSet<String> = self
+            .names
+            .iter()
+            .filter_map(|n| n.strip_prefix(&prefix)?.split_once('/'))
+            .map(|(sub_dir, _)| join(dir, sub_dir))
+            .collect();
+
+        Ok(dirs.into_iter().collect())
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let mut archive = self.archive.lock().unwrap();
+        let mut file = archive.by_name(path).map_err(zip_error)?;
+
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.names
+            .binary_search_by(|n| n.as_str().cmp(path))
+            .is_ok()
+    }
+
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+fn zip_error(e: ZipError) -> io::Error {
+    match e {
+        ZipError::Io(e) => e,
+        ZipError::FileNotFound => io::Error::new(io::ErrorKind::NotFound, e),
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
+
+/// Ordered stack of sources, e.g. vanilla first and resource packs after.
+///
+/// A file in a later layer replaces the file at the same path in every earlier layer, the way the
+/// game applies resource packs. Files are never merged: unlike the game, which combines the
+/// variants of a blockstate across packs, a blockstate in a later layer replaces every variant and
+/// multipart case of the earlier ones.
+#[derive(Default)]
+pub struct LayeredSource {
+    layers: Vec<(String, Box<dyn AssetSource>)>,
+}
+
+impl LayeredSource {
+    pub fn push(&mut self, name: String, source: Box<dyn AssetSource>) {
+        self.layers.push((name, source));
+    }
+
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Index of the layer `path` is read from.
+    pub fn provider_index(&self, path: &str) -> Option<usize> {
+        self.layers.iter().rposition(|(_, s)| s.exists(path))
+    }
+
+    /// Name of the layer `path` is read from.
+    pub fn provider(&self, path: &str) -> Option<&str> {
+        self.provider_index(path).map(|i| self.layers[i].0.as_str())
+    }
+
+    /// Union of `list` over all layers, failing only if `dir` is missing from every layer.
+    fn merge<F>(&self, dir: &str, list: F) -> io::Result<Vec<String>>
+    where
+        F: Fn(&dyn AssetSource) -> io::Result<Vec<String>>,
+    {
+        let mut paths = BTreeSet::new();
+        let mut found = false;
+
+        for (_, source) in &self.layers {
+            match list(source.as_ref()) {
+                Ok(p) => {
+                    found = true;
+                    paths.extend(p);
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !found {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Directory {} not found in {}", dir, self.describe()),
+            ));
+        }
+
+        Ok(paths.into_iter().collect())
+    }
+}
+
+impl AssetSource for LayeredSource {
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.merge(dir, |source| source.list(dir))
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.merge(dir, |source| source.list_dirs(dir))
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        match self.provider_index(path) {
+            Some(i) => self.layers[i].1.read(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} not found in {}", path, self.describe()),
+            )),
+        }
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.provider_index(path).is_some()
+    }
+
+    fn describe(&self) -> String {
+        self.layers
+            .iter()
+            .map(|(_, s)| s.describe())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
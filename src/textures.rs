@@ -1,13 +1,25 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
 
+use image::{ImageFormat, RgbaImage, imageops};
+use indexmap::IndexMap;
 use serde::Serialize;
 
 use crate::{
-    palette::{BlockTexture, FaceTexture, Rotation},
+    baked::BakedModel,
+    error::Error,
+    palette::{
+        BlockTexture, Color, CubeRotation, FaceTexture, Material, MaterialDisplay, Rotation,
+    },
     schema::{
-        blockstate::BlockState,
-        model::{Element, Model},
+        blockstate::{BlockState, ModelDefinition},
+        location::ResourceLocation,
     },
+    source::AssetSource,
+    voxel::VoxelGrid,
 };
 
 #[derive(Debug, Serialize, Clone)]
@@ -21,7 +33,7 @@ pub struct FaceTextureRefs {
 }
 
 impl BlockTexture {
-    fn to_face_texture_refs(&self) -> FaceTextureRefs {
+    pub fn to_face_texture_refs(&self) -> FaceTextureRefs {
         FaceTextureRefs {
             x: self.x.to_string(),
             nx: self.nx.to_string(),
@@ -33,103 +45,224 @@ impl BlockTexture {
     }
 }
 
+/// Textures of every weighted model of a blockstate variant, in blockstate order.
+///
+/// Faces with a `tintindex` get `tint`, see [`crate::tint`]. Fails if `blockstates` has no
+/// variant `blockstate_key` of `block_name`.
 pub fn get_block_textures(
-    block_name: &str,
+    block_name: &ResourceLocation,
     blockstate_key: &str,
-    models: &HashMap<String, Model>,
-    blockstates: &HashMap<String, BlockState>,
-) -> BlockTexture {
+    models: &HashMap<ResourceLocation, BakedModel>,
+    blockstates: &HashMap<ResourceLocation, BlockState>,
+    tint: Option<Color>,
+) -> Result<Vec<BlockTexture>, Error> {
     let blockstate = blockstates
         .get(block_name)
-        .expect("Blockstate should exist");
+        .ok_or_else(|| Error::MissingBlockstate {
+            block: block_name.clone(),
+        })?;
 
-    let m = blockstate
+    blockstate
         .variants
         .get(blockstate_key)
-        .expect("Blockstate key should exist")
-        .models()[0]; // TODO: multiple textures per block e.g. stone
-
-    let model = models.get(&m.model).expect("Model should exist");
-    let cube_element = full_cube_element(model, models);
-
-    let [down, up, north, south, west, east] = ["down", "up", "north", "south", "west", "east"]
-        .map(|f| {
-            resolve_texture_variable(
-                &cube_element
-                    .faces
-                    .get(f)
-                    .expect(&format!("Face texture of {} should be present", f))
-                    .texture,
-                &model.textures,
-                models,
-                &model.parent,
-            )
-        });
-
-    let mut block_texture = BlockTexture {
-        x: FaceTexture::new(east),
-        nx: FaceTexture::new(west),
-        y: FaceTexture::new(up),
-        ny: FaceTexture::new(down),
-        z: FaceTexture::new(north),
-        nz: FaceTexture::new(south),
+        .ok_or_else(|| Error::MissingVariant {
+            block: block_name.clone(),
+            key: blockstate_key.to_owned(),
+        })?
+        .models()
+        .into_iter()
+        .map(|m| get_model_textures(m, &block_name.to_string(), models, tint))
+        .collect()
+}
+
+/// Textures and UV regions of the full cube element of `m`, turned by the rotation of
+/// each face and then rotated as the blockstate specifies, honoring its `uvlock`.
+///
+/// Faces with a `tintindex` get `tint`.
+pub fn get_model_textures(
+    m: &ModelDefinition,
+    referenced_by: &str,
+    models: &HashMap<ResourceLocation, BakedModel>,
+    tint: Option<Color>,
+) -> Result<BlockTexture, Error> {
+    let model = models.get(&m.model).ok_or_else(|| Error::MissingModel {
+        model: m.model.clone(),
+        referenced_by: referenced_by.to_owned(),
+    })?;
+    let cube_element = model.full_cube_element()?;
+
+    let face = |name: &str| -> Result<FaceTexture, Error> {
+        let face = cube_element.faces.get(name).ok_or_else(|| Error::NotCube {
+            model: m.model.clone(),
+        })?;
+
+        // Model faces turn clockwise as seen from outside the block, face textures the
+        // other way
+        let rotation =
+            Rotation::normalized(face.rotation).ok_or_else(|| Error::InvalidFaceRotation {
+                model: m.model.clone(),
+                face: name.to_owned(),
+                rotation: face.rotation,
+            })?;
+
+        Ok(FaceTexture::new(model.face_texture(face)?)
+            .with_uv(face.uv)
+            .with_tint(tint.filter(|_| face.tintindex.is_some()))
+            .add_rotation(-rotation))
     };
 
-    block_texture = block_texture.rotate_x(Rotation::from_degrees(m.x).unwrap());
-    block_texture = block_texture.rotate_y(Rotation::from_degrees(m.y).unwrap());
-    block_texture = block_texture.rotate_z(Rotation::from_degrees(m.z).unwrap());
+    let block_texture = BlockTexture {
+        ny: face("down")?,
+        y: face("up")?,
+        z: face("north")?,
+        nz: face("south")?,
+        nx: face("west")?,
+        x: face("east")?,
+    };
 
-    block_texture
+    let [x, y, z] = model_rotation(m, referenced_by)?;
+    Ok(block_texture.rotate(CubeRotation::from_xyz(x, y, z), m.uvlock))
 }
 
-fn resolve_texture_variable(
-    var_name: &str,
-    textures: &HashMap<String, String>,
-    models: &HashMap<String, Model>,
-    parent: &Option<String>,
-) -> String {
-    let mut textures = textures.clone();
-    let mut parent = parent;
-
-    while let Some(parent_name) = parent {
-        let par = models.get(parent_name).expect("Model should exist");
-
-        for (k, v) in par.textures.clone() {
-            if !textures.contains_key(&k) {
-                textures.insert(k, v);
-            }
-        }
+/// Reads and decodes the PNG of `texture`, `textures/<path>.png` in its namespace.
+pub fn read_texture_image(
+    texture: &ResourceLocation,
+    source: &dyn AssetSource,
+) -> Result<RgbaImage, Error> {
+    read_image(texture.asset_path("textures", "png"), source)
+}
 
-        parent = &par.parent;
-    }
+/// Reads and decodes the PNG at `path`.
+pub fn read_image(path: String, source: &dyn AssetSource) -> Result<RgbaImage, Error> {
+    let bytes = source.read(&path).map_err(|e| Error::Io {
+        path: path.clone(),
+        source: e,
+    })?;
 
-    let mut var_name = var_name.strip_prefix('#').unwrap_or(var_name);
+    image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| Error::Image {
+            path,
+            message: e.to_string(),
+        })
+}
 
-    while let Some(value) = textures.get(var_name) {
-        if value.starts_with('#') {
-            var_name = value.strip_prefix('#').unwrap();
-        } else {
-            return value.clone();
+/// Pixels `face` shows of `image`: its UV region, mirrored, turned counter-clockwise and
+/// tinted.
+pub fn render_face(face: &FaceTexture, image: &RgbaImage) -> RgbaImage {
+    let [u1, v1, u2, v2] = face.uv.unwrap_or([0.0, 0.0, 16.0, 16.0]);
+    let (x, y) = face.uv_pixels(image.width(), image.height());
+    let mut pixels =
+        imageops::crop_imm(image, x.start, y.start, x.len() as u32, y.len() as u32).to_image();
+
+    if (u1 > u2) != face.flip_x {
+        imageops::flip_horizontal_in_place(&mut pixels);
+    }
+    if (v1 > v2) != face.flip_y {
+        imageops::flip_vertical_in_place(&mut pixels);
+    }
+    let mut pixels = match face.rotation {
+        Rotation::CCW0 => pixels,
+        Rotation::CCW90 => imageops::rotate270(&pixels),
+        Rotation::CCW180 => imageops::rotate180(&pixels),
+        Rotation::CCW270 => imageops::rotate90(&pixels),
+    };
+
+    if let Some(tint) = face.tint {
+        for pixel in pixels.pixels_mut() {
+            for (c, t) in pixel.0.iter_mut().zip([tint.r, tint.g, tint.b]) {
+                *c = (*c as u16 * t as u16 / 255) as u8;
+            }
         }
     }
+    pixels
+}
 
-    panic!("Texture variable {var_name} should be resolvable")
+/// Quarter turns of `m` around x, y and z, with angles taken modulo 360.
+///
+/// The game turns models clockwise around x and y looking from the positive end of the
+/// axis, so those are turned around to the counter-clockwise [`Rotation`]. `z`, which the
+/// game does not read, is taken as counter-clockwise already.
+pub fn model_rotation(m: &ModelDefinition, referenced_by: &str) -> Result<[Rotation; 3], Error> {
+    let mut rotation = [Rotation::CCW0; 3];
+    for (r, degrees) in rotation.iter_mut().zip([m.x, m.y, m.z]) {
+        *r = Rotation::normalized(degrees).ok_or_else(|| Error::InvalidRotation {
+            model: m.model.clone(),
+            referenced_by: referenced_by.to_owned(),
+            degrees,
+        })?;
+    }
+    let [x, y, z] = rotation;
+    Ok([-x, -y, z])
 }
 
-fn full_cube_element(model: &Model, models: &HashMap<String, Model>) -> Element {
-    if let Some(cube) = model
-        .elements
-        .iter()
-        .find(|e| e.from == [0.0, 0.0, 0.0] && e.to == [16.0, 16.0, 16.0])
-    {
-        return cube.clone();
+/// Copies the PNGs referenced by `materials` and the textures of `volumes` from `source`
+/// to `output_dir/<namespace>/<path>.png`. Animation frames are written from `frames`.
+///
+/// Returns the number of copied and failed textures.
+pub fn copy_textures_from_materials<'a>(
+    materials: impl Iterator<Item = &'a Material>,
+    volumes: impl Iterator<Item = &'a VoxelGrid>,
+    frames: &IndexMap<ResourceLocation, RgbaImage>,
+    source: &dyn AssetSource,
+    output_dir: &Path,
+) -> (usize, usize) {
+    let mut textures = HashSet::new();
+
+    for material in materials {
+        let block_textures = match &material.display {
+            MaterialDisplay::Texture(texture) => std::slice::from_ref(texture),
+            MaterialDisplay::TextureAnimation { frames, .. } => frames.as_slice(),
+            _ => &[],
+        };
+        for t in block_textures.iter().flat_map(BlockTexture::faces) {
+            textures.insert(t.path.clone());
+        }
+    }
+    for grid in volumes {
+        textures.extend(grid.textures.iter().cloned());
     }
 
-    if let Some(parent_name) = &model.parent {
-        let parent_model = models.get(parent_name).expect("Model should exist");
+    let mut copied = 0;
+    let mut failed = 0;
 
-        return full_cube_element(&parent_model, models);
+    for t in textures {
+        let source_path = t.asset_path("textures", "png");
+        let output_path = output_dir.join(format!("{}.png", t.file_stem()));
+
+        if let Some(frame) = frames.get(&t) {
+            if let Err(e) = output_path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .map_err(|e| e.to_string())
+                .and_then(|_| {
+                    frame
+                        .save_with_format(&output_path, ImageFormat::Png)
+                        .map_err(|e| e.to_string())
+                })
+            {
+                eprintln!("Failed to write frame {}: {}", t, e);
+                failed += 1;
+            } else {
+                copied += 1;
+            }
+        } else if source.exists(&source_path) {
+            if let Err(e) = source.read(&source_path).and_then(|bytes| {
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&output_path, bytes)
+            }) {
+                eprintln!("Failed to copy {}: {}", source_path, e);
+                failed += 1;
+            } else {
+                copied += 1;
+            }
+        } else {
+            eprintln!("Texture file not found: {}", source_path);
+            failed += 1;
+        }
     }
 
-    panic!("Model should have a full cube element");
+    (copied, failed)
 }
@@ -0,0 +1,173 @@
+//! Colors the game multiplies into model faces with a `tintindex`, chosen per block like
+//! its block colors choose them.
+
+use std::collections::BTreeMap;
+
+use image::RgbaImage;
+
+use crate::{
+    error::Diagnostics, palette::Color, schema::location::ResourceLocation, source::AssetSource,
+    textures::read_texture_image,
+};
+
+pub const BIRCH_FOLIAGE: Color = Color::rgb(0x80a755);
+pub const SPRUCE_FOLIAGE: Color = Color::rgb(0x619961);
+pub const MANGROVE_FOLIAGE: Color = Color::rgb(0x92c648);
+pub const LILY_PAD: Color = Color::rgb(0x208030);
+/// Stems grown into a pumpkin or melon, the color of a fully grown stem
+pub const ATTACHED_STEM: Color = Color::rgb(0xe0c71c);
+/// Water color of most biomes, plains among them
+pub const WATER: Color = Color::rgb(0x3f76e4);
+
+/// Temperature and downfall of a biome, which pick its grass and foliage colors from the
+/// colormaps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Climate {
+    pub temperature: f32,
+    pub downfall: f32,
+}
+
+impl Climate {
+    pub const PLAINS: Climate = Climate {
+        temperature: 0.8,
+        downfall: 0.4,
+    };
+}
+
+impl Default for Climate {
+    fn default() -> Self {
+        Self::PLAINS
+    }
+}
+
+/// Where the tint of a block comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TintSource {
+    /// `textures/colormap/grass.png` at the biome climate
+    Grass,
+    /// `textures/colormap/foliage.png` at the biome climate
+    Foliage,
+    /// The same color in every biome
+    Fixed(Color),
+}
+
+/// How the game tints the faces with a `tintindex` of vanilla `block` in `state`, `None` if
+/// it leaves them untinted. All tint indices of a block get the same color.
+pub fn tint_source(
+    block: &ResourceLocation,
+    state: &BTreeMap<String, String>,
+) -> Option<TintSource> {
+    if block.namespace != "minecraft" {
+        return None;
+    }
+
+    let source = match block.path.as_str() {
+        "grass_block" | "short_grass" | "grass" | "tall_grass" | "fern" | "large_fern"
+        | "potted_fern" | "sugar_cane" | "bush" => TintSource::Grass,
+        "oak_leaves" | "jungle_leaves" | "acacia_leaves" | "dark_oak_leaves" | "vine" => {
+            TintSource::Foliage
+        }
+        "birch_leaves" => TintSource::Fixed(BIRCH_FOLIAGE),
+        "spruce_leaves" => TintSource::Fixed(SPRUCE_FOLIAGE),
+        "mangrove_leaves" => TintSource::Fixed(MANGROVE_FOLIAGE),
+        "lily_pad" => TintSource::Fixed(LILY_PAD),
+        "water" | "bubble_column" | "water_cauldron" => TintSource::Fixed(WATER),
+        "redstone_wire" => {
+            let power = state.get("power").and_then(|p| p.parse().ok());
+            TintSource::Fixed(redstone_color(power.unwrap_or(0)))
+        }
+        "pumpkin_stem" | "melon_stem" => {
+            let age = state.get("age").and_then(|a| a.parse().ok());
+            TintSource::Fixed(stem_color(age.unwrap_or(0)))
+        }
+        "attached_pumpkin_stem" | "attached_melon_stem" => TintSource::Fixed(ATTACHED_STEM),
+        _ => return None,
+    };
+    Some(source)
+}
+
+/// Color of redstone wire carrying `power`, from 0 to 15.
+pub fn redstone_color(power: u8) -> Color {
+    let f = power.min(15) as f32 / 15.0;
+    let r = f * 0.6 + if f > 0.0 { 0.4 } else { 0.3 };
+    let g = (f * f * 0.7 - 0.5).clamp(0.0, 1.0);
+    let b = (f * f * 0.6 - 0.7).clamp(0.0, 1.0);
+
+    Color {
+        r: (r * 255.0) as u8,
+        g: (g * 255.0) as u8,
+        b: (b * 255.0) as u8,
+        a: 255,
+    }
+}
+
+/// Color of a pumpkin or melon stem of `age`, from 0 to 7, greener the younger it is.
+pub fn stem_color(age: u8) -> Color {
+    let age = age.min(7);
+
+    Color {
+        r: age * 32,
+        g: 255 - age * 8,
+        b: age * 4,
+        a: 255,
+    }
+}
+
+/// Grass and foliage colormaps of the assets, sampled at the climate of one biome.
+#[derive(Default)]
+pub struct Tints {
+    pub climate: Climate,
+    grass: Option<RgbaImage>,
+    foliage: Option<RgbaImage>,
+}
+
+impl Tints {
+    /// Reads the colormaps from `source`.
+    ///
+    /// Without a colormap the faces it would tint stay untinted. Colormaps that cannot be
+    /// read are also reported to `diagnostics`.
+    pub fn load(source: &dyn AssetSource, diagnostics: &mut Diagnostics) -> Self {
+        let mut colormap = |name: &str| {
+            let texture = ResourceLocation::new("minecraft", format!("colormap/{}", name));
+            if !source.exists(&texture.asset_path("textures", "png")) {
+                return None;
+            }
+            read_texture_image(&texture, source)
+                .map_err(|e| diagnostics.push(e))
+                .ok()
+        };
+
+        Self {
+            climate: Climate::default(),
+            grass: colormap("grass"),
+            foliage: colormap("foliage"),
+        }
+    }
+
+    /// Tint of the faces with a `tintindex` of `block` in `state`, see [`tint_source`].
+    pub fn color(
+        &self,
+        block: &ResourceLocation,
+        state: &BTreeMap<String, String>,
+    ) -> Option<Color> {
+        match tint_source(block, state)? {
+            TintSource::Grass => self.grass.as_ref().map(|c| sample(c, self.climate)),
+            TintSource::Foliage => self.foliage.as_ref().map(|c| sample(c, self.climate)),
+            TintSource::Fixed(color) => Some(color),
+        }
+    }
+}
+
+/// Color of a colormap at `climate`, temperature falling to the right and downfall
+/// times temperature falling downwards.
+fn sample(colormap: &RgbaImage, climate: Climate) -> Color {
+    let temperature = climate.temperature.clamp(0.0, 1.0);
+    let downfall = climate.downfall.clamp(0.0, 1.0) * temperature;
+
+    let (width, height) = colormap.dimensions();
+    let x = ((1.0 - temperature) * (width - 1) as f32) as u32;
+    let y = ((1.0 - downfall) * (height - 1) as f32) as u32;
+    let [r, g, b, _] = colormap.get_pixel(x, y).0;
+
+    Color { r, g, b, a: 255 }
+}
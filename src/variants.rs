@@ -3,62 +3,79 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use serde::Serialize;
 
 use crate::{
-    schema::blockstate::{BlockState, MultipartCase, MultipartCondition, PropertyMatch},
+    registry::{BlockDefinition, BlockRegistry},
+    schema::{
+        blockstate::{BlockState, MultipartCase, MultipartCondition, PropertyMatch, parse_state},
+        location::ResourceLocation,
+    },
     textures::FaceTextureRefs,
 };
 
 #[derive(Clone, Debug, Serialize)]
 pub struct BlockVariant {
-    pub name: String,
+    pub name: ResourceLocation,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blockstate: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub textures: Option<FaceTextureRefs>,
+    /// Whether this is the state the game places by default.
+    ///
+    /// Only known for blocks without properties unless a [`BlockRegistry`] is available.
+    #[serde(skip_serializing_if = "is_false")]
+    pub default: bool,
 }
 
-pub fn get_all_block_variants(blockstates: &HashMap<String, BlockState>) -> Vec<BlockVariant> {
+impl BlockVariant {
+    /// Block id as used by the palette, e.g. `minecraft:oak_log#axis=y`.
+    pub fn id(&self) -> String {
+        if let Some(b) = &self.blockstate {
+            format!("{}#{}", self.name, b)
+        } else {
+            self.name.to_string()
+        }
+    }
+}
+
+/// Variants named by the blockstates, one per variant key or inferred multipart combination.
+///
+/// `registry` is only used to tell which variant is the default one.
+pub fn get_all_block_variants(
+    blockstates: &HashMap<ResourceLocation, BlockState>,
+    registry: Option<&BlockRegistry>,
+) -> Vec<BlockVariant> {
     let mut all_variants = Vec::new();
 
     for (block_name, blockstate) in blockstates {
-        if !blockstate.variants.is_empty() {
-            for variant_key in blockstate.variants.keys() {
-                if variant_key.is_empty() {
-                    all_variants.push(BlockVariant {
-                        name: block_name.clone(),
-                        blockstate: None,
-                        textures: None,
-                    });
-                } else {
-                    all_variants.push(BlockVariant {
-                        name: block_name.clone(),
-                        blockstate: Some(variant_key.clone()),
-                        textures: None,
-                    });
-                }
-            }
-        } else if !blockstate.multipart.is_empty() {
-            let properties = multipart_properties(&blockstate.multipart);
-            let combinations = multipart_combinations(&properties);
-
-            for combo in combinations {
-                if combo.is_empty() {
-                    all_variants.push(BlockVariant {
-                        name: block_name.clone(),
-                        blockstate: None,
-                        textures: None,
-                    });
-                } else {
-                    all_variants.push(BlockVariant {
-                        name: block_name.clone(),
-                        blockstate: Some(combo),
-                        textures: None,
-                    });
-                }
-            }
-        } else {
+        let definition = registry.and_then(|r| r.blocks.get(block_name));
+        all_variants.extend(blockstate_variants(block_name, blockstate, definition));
+    }
+
+    all_variants.sort_by(|v1, v2| v1.name.cmp(&v2.name));
+
+    all_variants
+}
+
+/// Every state of every block, taken from `registry` where the block is registered there.
+///
+/// Blocks missing from the registry fall back to the states inferred from their blockstate.
+pub fn get_all_block_states(
+    blockstates: &HashMap<ResourceLocation, BlockState>,
+    registry: &BlockRegistry,
+) -> Vec<BlockVariant> {
+    let mut all_variants = Vec::new();
+
+    for (block_name, blockstate) in blockstates {
+        let Some(definition) = registry.blocks.get(block_name) else {
+            all_variants.extend(blockstate_variants(block_name, blockstate, None));
+            continue;
+        };
+
+        let default_key = definition.default_state_key();
+        for state in definition.states() {
             all_variants.push(BlockVariant {
                 name: block_name.clone(),
-                blockstate: None,
+                default: state == default_key,
+                blockstate: (!state.is_empty()).then_some(state),
                 textures: None,
             });
         }
@@ -69,15 +86,50 @@ pub fn get_all_block_variants(blockstates: &HashMap<String, BlockState>) -> Vec<
     all_variants
 }
 
+fn blockstate_variants(
+    block_name: &ResourceLocation,
+    blockstate: &BlockState,
+    definition: Option<&BlockDefinition>,
+) -> Vec<BlockVariant> {
+    let keys = if !blockstate.variants.is_empty() {
+        blockstate.variants.keys().cloned().collect()
+    } else if !blockstate.multipart.is_empty() {
+        state_combinations(&multipart_properties(&blockstate.multipart))
+    } else {
+        vec![String::new()]
+    };
+
+    let is_default = |key: &str| match definition {
+        None => key.is_empty(),
+        Some(d) if blockstate.is_variants() => {
+            blockstate.variant_key(&d.default_state) == Some(key)
+        }
+        Some(d) => parse_state(key)
+            .iter()
+            .all(|(k, v)| d.default_state.get(k) == Some(v)),
+    };
+
+    keys.into_iter()
+        .map(|key| BlockVariant {
+            name: block_name.clone(),
+            default: is_default(&key),
+            blockstate: (!key.is_empty()).then_some(key),
+            textures: None,
+        })
+        .collect()
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 fn multipart_properties(multipart: &[MultipartCase]) -> BTreeMap<String, BTreeSet<String>> {
     fn add_properties(
         property_match: &PropertyMatch,
         properties: &mut BTreeMap<String, BTreeSet<String>>,
     ) {
         for prop in property_match.properties.keys() {
-            let prop_set = properties
-                .entry(prop.to_owned())
-                .or_insert_with(BTreeSet::new);
+            let prop_set = properties.entry(prop.to_owned()).or_default();
             for p in property_match.property_values(prop).unwrap_or(vec![]) {
                 prop_set.insert(p.to_owned());
             }
@@ -122,16 +174,25 @@ fn multipart_properties(multipart: &[MultipartCase]) -> BTreeMap<String, BTreeSe
     properties
 }
 
-fn multipart_combinations(properties: &BTreeMap<String, BTreeSet<String>>) -> Vec<String> {
+/// Cartesian product of property values as blockstate keys such as `axis=x,waterlogged=true`.
+///
+/// Yields a single empty key if there are no properties.
+pub(crate) fn state_combinations<'a, V>(properties: &'a BTreeMap<String, V>) -> Vec<String>
+where
+    &'a V: IntoIterator<Item = &'a String>,
+{
     if properties.is_empty() {
         return vec![String::new()];
     }
 
-    let prop_vec: Vec<(&String, &BTreeSet<String>)> = properties.iter().collect();
+    let prop_vec: Vec<(&String, Vec<&String>)> = properties
+        .iter()
+        .map(|(k, v)| (k, v.into_iter().collect()))
+        .collect();
     let mut results = Vec::new();
 
     fn recurse(
-        prop_vec: &[(&String, &BTreeSet<String>)],
+        prop_vec: &[(&String, Vec<&String>)],
         index: usize,
         current: Vec<(String, String)>,
         results: &mut Vec<String>,
@@ -150,10 +211,10 @@ fn multipart_combinations(properties: &BTreeMap<String, BTreeSet<String>>) -> Ve
             return;
         }
 
-        let (prop_name, values) = prop_vec[index];
+        let (prop_name, values) = &prop_vec[index];
         for value in values {
             let mut next = current.clone();
-            next.push((prop_name.clone(), value.clone()));
+            next.push(((*prop_name).clone(), (*value).clone()));
             recurse(prop_vec, index + 1, next, results);
         }
     }
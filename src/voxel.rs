@@ -0,0 +1,574 @@
+//! Voxel grids of block models and the `.voxel` files they are stored in.
+//!
+//! # File format
+//!
+//! A `.voxel` file holds one [`VoxelGrid`]. All numbers are little endian.
+//!
+//! - magic `MBVX` followed by a `u8` version, currently 2
+//! - `u16` grid size N
+//! - `u16` texture count, then for each texture a `u16` byte length and the resource
+//!   location in UTF-8
+//! - the N³ cells with `x` varying fastest, then `y`, then `z`, as alternating runs until
+//!   all cells are covered: a `u32` number of empty cells, then a `u32` number of filled
+//!   cells followed by one record for each
+//! - a filled cell record is a `u8` mask with bit `i` set if face `i` of [`FACE_NAMES`] has
+//!   a sample, then a `u8` mask with bit `i` set if the sample of face `i` is tinted, then
+//!   for each sample a `u16` texture index and the `u` and `v` coordinates as `u16` in
+//!   1/256 of a pixel
+//!
+//! Version 1 files have no tint mask and are read with every sample untinted.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    baked::BakedModel,
+    error::Error,
+    palette::Rotation,
+    schema::{
+        location::ResourceLocation,
+        model::{Axis, Element, ElementRotation, Face},
+    },
+};
+
+/// Extension of voxel grid files.
+pub const VOXEL_EXTENSION: &str = "voxel";
+
+/// Largest grid size volumes are voxelized and read with. Grids are dense, so this keeps
+/// one grid below 20 MB.
+pub const MAX_GRID_SIZE: usize = 64;
+
+const MAGIC: &[u8; 4] = b"MBVX";
+const VERSION: u8 = 2;
+
+/// Model face names in the order voxel faces are stored: `+x`, `-x`, `+y`, `-y`, `+z`, `-z`.
+pub const FACE_NAMES: [&str; 6] = ["east", "west", "up", "down", "south", "north"];
+
+const DIRECTIONS: [[i32; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+/// Where a voxel face samples its color from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceSample {
+    /// Index into [`VoxelGrid::textures`]
+    pub texture: u16,
+    /// Texture coordinates in model units, `0..16` spanning the whole texture
+    pub uv: [f32; 2],
+    /// Sampled from a face with a `tintindex`, so [`crate::palette::BlockVolume::tint`]
+    /// multiplies into it
+    pub tinted: bool,
+}
+
+/// A filled voxel with samples for its faces that are not hidden by neighbours.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Voxel {
+    /// Indexed like [`FACE_NAMES`]
+    pub faces: [Option<FaceSample>; 6],
+}
+
+/// Cubic occupancy grid over a block, in model coordinates: `x` east, `y` up, `z` south.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoxelGrid {
+    pub size: usize,
+    /// Textures sampled by the voxel faces
+    pub textures: Vec<ResourceLocation>,
+    voxels: Vec<Option<Voxel>>,
+}
+
+impl VoxelGrid {
+    /// Empty grid of `size`³ voxels, which takes `size`³ cells of memory whatever is
+    /// filled, see [`MAX_GRID_SIZE`].
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            textures: Vec::new(),
+            voxels: vec![None; size * size * size],
+        }
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        x + self.size * (y + self.size * z)
+    }
+
+    pub fn get(&self, pos: [usize; 3]) -> Option<&Voxel> {
+        self.voxels[self.index(pos)].as_ref()
+    }
+
+    pub fn set(&mut self, pos: [usize; 3], voxel: Option<Voxel>) {
+        let i = self.index(pos);
+        self.voxels[i] = voxel;
+    }
+
+    /// Positions and contents of the filled voxels, `x` varying fastest.
+    pub fn voxels(&self) -> impl Iterator<Item = ([usize; 3], &Voxel)> {
+        let size = self.size;
+
+        self.voxels.iter().enumerate().filter_map(move |(i, v)| {
+            v.as_ref()
+                .map(|v| ([i % size, i / size % size, i / (size * size)], v))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.iter().all(Option::is_none)
+    }
+
+    /// Whether any face sample is tinted.
+    pub fn is_tinted(&self) -> bool {
+        self.voxels()
+            .any(|(_, v)| v.faces.iter().flatten().any(|s| s.tinted))
+    }
+
+    pub fn texture_index(&mut self, texture: &ResourceLocation) -> u16 {
+        match self.textures.iter().position(|t| t == texture) {
+            Some(i) => i as u16,
+            None => {
+                self.textures.push(texture.clone());
+                (self.textures.len() - 1) as u16
+            }
+        }
+    }
+
+    /// Rotates the grid around the block center, see [`crate::shapes::Cuboid::rotated`].
+    pub fn rotated(&self, [x, y, z]: [Rotation; 3]) -> Self {
+        let mut grid = self.clone();
+        let max = self.size - 1;
+
+        for _ in 0..x.degrees() / 90 {
+            grid = grid.map(|[x, y, z]| [x, max - z, y], |[x, y, z]| [x, -z, y]);
+        }
+        for _ in 0..y.degrees() / 90 {
+            grid = grid.map(|[x, y, z]| [z, y, max - x], |[x, y, z]| [z, y, -x]);
+        }
+        for _ in 0..z.degrees() / 90 {
+            grid = grid.map(|[x, y, z]| [max - y, x, z], |[x, y, z]| [-y, x, z]);
+        }
+
+        grid
+    }
+
+    fn map(
+        &self,
+        position: impl Fn([usize; 3]) -> [usize; 3],
+        direction: impl Fn([i32; 3]) -> [i32; 3],
+    ) -> Self {
+        let face_map = DIRECTIONS.map(|d| direction_index(direction(d)));
+        let mut grid = Self {
+            size: self.size,
+            textures: self.textures.clone(),
+            voxels: vec![None; self.voxels.len()],
+        };
+
+        for (pos, voxel) in self.voxels() {
+            let mut rotated = Voxel::default();
+            for (i, face) in voxel.faces.iter().enumerate() {
+                rotated.faces[face_map[i]] = *face;
+            }
+            grid.set(position(pos), Some(rotated));
+        }
+
+        grid
+    }
+
+    /// Fills the voxels empty here with those of `other`, which must have the same size.
+    pub fn merge(&mut self, other: &VoxelGrid) {
+        for (pos, voxel) in other.voxels() {
+            if self.get(pos).is_some() {
+                continue;
+            }
+
+            let mut voxel = *voxel;
+            for sample in voxel.faces.iter_mut().flatten() {
+                sample.texture = self.texture_index(&other.textures[sample.texture as usize]);
+            }
+            self.set(pos, Some(voxel));
+        }
+
+        self.cull_hidden_faces();
+    }
+
+    /// Drops the face samples of faces that touch another filled voxel.
+    fn cull_hidden_faces(&mut self) {
+        let size = self.size as i32;
+
+        for i in 0..self.voxels.len() {
+            let Some(mut voxel) = self.voxels[i] else {
+                continue;
+            };
+            let pos = [
+                i % self.size,
+                i / self.size % self.size,
+                i / (self.size * self.size),
+            ];
+
+            for (face, d) in DIRECTIONS.iter().enumerate() {
+                let n = [0, 1, 2].map(|a| pos[a] as i32 + d[a]);
+                if n.iter().all(|&c| (0..size).contains(&c))
+                    && self.get(n.map(|c| c as usize)).is_some()
+                {
+                    voxel.faces[face] = None;
+                }
+            }
+
+            self.voxels[i] = Some(voxel);
+        }
+    }
+}
+
+impl VoxelGrid {
+    /// Writes the grid in the `.voxel` format described in the [module docs](self).
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let size = u16::try_from(self.size).map_err(|_| invalid_data("Grid too large"))?;
+        let texture_count =
+            u16::try_from(self.textures.len()).map_err(|_| invalid_data("Too many textures"))?;
+
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        w.write_all(&size.to_le_bytes())?;
+        w.write_all(&texture_count.to_le_bytes())?;
+        for texture in &self.textures {
+            let name = texture.to_string();
+            let len =
+                u16::try_from(name.len()).map_err(|_| invalid_data("Texture name too long"))?;
+            w.write_all(&len.to_le_bytes())?;
+            w.write_all(name.as_bytes())?;
+        }
+
+        let mut cells = self.voxels.iter().peekable();
+        while cells.peek().is_some() {
+            let mut empty = 0u32;
+            while cells.next_if(|c| c.is_none()).is_some() {
+                empty += 1;
+            }
+
+            let mut filled = Vec::new();
+            while let Some(Some(voxel)) = cells.next_if(|c| c.is_some()) {
+                filled.push(voxel);
+            }
+
+            w.write_all(&empty.to_le_bytes())?;
+            w.write_all(&(filled.len() as u32).to_le_bytes())?;
+            for voxel in filled {
+                write_voxel(&mut w, voxel)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a grid written by [`VoxelGrid::write_to`].
+    pub fn read_from<R: Read>(mut r: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a voxel file"));
+        }
+        let version = read_u8(&mut r)?;
+        if !(1..=VERSION).contains(&version) {
+            return Err(invalid_data(&format!(
+                "Unsupported voxel file version {}",
+                version
+            )));
+        }
+
+        let size = read_u16(&mut r)? as usize;
+        if size > MAX_GRID_SIZE {
+            return Err(invalid_data(&format!("Grid size {} is too large", size)));
+        }
+        let mut grid = VoxelGrid::new(size);
+
+        for _ in 0..read_u16(&mut r)? {
+            let mut name = vec![0; read_u16(&mut r)? as usize];
+            r.read_exact(&mut name)?;
+            let name = String::from_utf8(name).map_err(|_| invalid_data("Invalid texture name"))?;
+            grid.textures
+                .push(name.parse().map_err(|e: String| invalid_data(&e))?);
+        }
+
+        let mut i = 0;
+        while i < grid.voxels.len() {
+            let empty = read_u32(&mut r)? as usize;
+            let filled = read_u32(&mut r)? as usize;
+            if i + empty + filled > grid.voxels.len() {
+                return Err(invalid_data("More cells than the grid holds"));
+            }
+            i += empty;
+
+            for _ in 0..filled {
+                let voxel = read_voxel(&mut r, version, grid.textures.len())?;
+                grid.voxels[i] = Some(voxel);
+                i += 1;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    /// Writes the grid to a `.voxel` file, creating its parent directories.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut w = io::BufWriter::new(fs::File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()
+    }
+}
+
+fn write_voxel<W: Write>(w: &mut W, voxel: &Voxel) -> io::Result<()> {
+    let mask = |f: fn(&FaceSample) -> bool| {
+        (0..6)
+            .filter(|&i| voxel.faces[i].as_ref().is_some_and(f))
+            .fold(0u8, |mask, i| mask | 1 << i)
+    };
+    w.write_all(&[mask(|_| true), mask(|s| s.tinted)])?;
+
+    for sample in voxel.faces.iter().flatten() {
+        w.write_all(&sample.texture.to_le_bytes())?;
+        for c in sample.uv {
+            w.write_all(&((c.clamp(0.0, 255.0) * 256.0).round() as u16).to_le_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_voxel<R: Read>(r: &mut R, version: u8, texture_count: usize) -> io::Result<Voxel> {
+    let mask = read_u8(r)?;
+    let tinted = if version >= 2 { read_u8(r)? } else { 0 };
+    let mut voxel = Voxel::default();
+
+    for (i, face) in voxel.faces.iter_mut().enumerate() {
+        if mask & 1 << i == 0 {
+            continue;
+        }
+
+        let texture = read_u16(r)?;
+        if texture as usize >= texture_count {
+            return Err(invalid_data("Texture index out of range"));
+        }
+        let uv = [read_u16(r)?, read_u16(r)?].map(|c| c as f32 / 256.0);
+        *face = Some(FaceSample {
+            texture,
+            uv,
+            tinted: tinted & 1 << i != 0,
+        });
+    }
+
+    Ok(voxel)
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+    let mut buf = [0; 2];
+    r.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+fn direction_index(d: [i32; 3]) -> usize {
+    DIRECTIONS
+        .iter()
+        .position(|&x| x == d)
+        .expect("axis aligned directions stay axis aligned when rotated by 90 degrees")
+}
+
+/// Fills a `size`³ grid with the elements of `model`, sampling each face from the element
+/// face pointing the same way.
+///
+/// Element rotations of up to 45 degrees either way are applied, including rescaling,
+/// larger ones are an error as the game rejects them too. Elements thinner
+/// than a voxel, like the planes of plants, fill the layer of voxels they pass through.
+/// Where elements overlap the first one wins.
+pub fn voxelize(model: &BakedModel, size: usize) -> Result<VoxelGrid, Error> {
+    let mut grid = VoxelGrid::new(size);
+    let voxel_size = 16.0 / size as f32;
+
+    let mut textures = Vec::new();
+    for element in &model.elements {
+        if let Some(r) = &element.rotation
+            && r.angle.abs() > 45.0
+        {
+            return Err(Error::InvalidElementRotation {
+                model: model.name.clone(),
+                angle: r.angle,
+            });
+        }
+
+        let mut faces = [None; 6];
+        for (i, name) in FACE_NAMES.iter().enumerate() {
+            if let Some(face) = element.faces.get(*name) {
+                faces[i] = Some((face, grid.texture_index(&model.face_texture(face)?)));
+            }
+        }
+        textures.push(faces);
+    }
+
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                let center = [x, y, z].map(|c| (c as f32 + 0.5) * voxel_size);
+
+                let voxel = model.elements.iter().zip(&textures).find_map(|(e, faces)| {
+                    let local = to_element_space(e.rotation.as_ref(), center);
+                    contains(e, local, voxel_size).then(|| sample_faces(e, faces, local))
+                });
+                grid.set([x, y, z], voxel);
+            }
+        }
+    }
+
+    grid.cull_hidden_faces();
+    Ok(grid)
+}
+
+fn contains(element: &Element, p: [f32; 3], voxel_size: f32) -> bool {
+    (0..3).all(|a| {
+        let (from, to) = (element.from[a], element.to[a]);
+        if to - from >= voxel_size {
+            from <= p[a] && p[a] < to
+        } else {
+            (p[a] - (from + to) / 2.0).abs() <= voxel_size / 2.0
+        }
+    })
+}
+
+fn sample_faces(element: &Element, faces: &[Option<(&Face, u16)>; 6], local: [f32; 3]) -> Voxel {
+    let mut voxel = Voxel::default();
+
+    for (i, d) in DIRECTIONS.iter().enumerate() {
+        // The element face closest to facing the same way once the element is rotated
+        let d = d.map(|c| c as f32);
+        let dir = element
+            .rotation
+            .as_ref()
+            .map_or(d, |r| rotate_vector(r, d, -1.0));
+        let axis = (0..3)
+            .max_by(|&a, &b| dir[a].abs().total_cmp(&dir[b].abs()))
+            .unwrap();
+        let mut snapped = [0; 3];
+        snapped[axis] = dir[axis].signum() as i32;
+
+        let face_index = direction_index(snapped);
+        if let Some((face, texture)) = faces[face_index] {
+            voxel.faces[i] = Some(FaceSample {
+                texture,
+                uv: face_uv(element, face, face_index, local),
+                tinted: face.tintindex.is_some(),
+            });
+        }
+    }
+
+    voxel
+}
+
+/// Texture coordinates of the point `p` projected on a face of `element`.
+fn face_uv(element: &Element, face: &Face, face_index: usize, p: [f32; 3]) -> [f32; 2] {
+    let (from, to) = (element.from, element.to);
+    let fraction = |a: usize, reversed: bool| {
+        let size = to[a] - from[a];
+        if size <= 0.0 {
+            return 0.5;
+        }
+        let f = ((p[a] - from[a]) / size).clamp(0.0, 1.0);
+        if reversed { 1.0 - f } else { f }
+    };
+
+    // Horizontal and vertical position on the face as seen from outside, top left at 0
+    let (s, t) = match FACE_NAMES[face_index] {
+        "east" => (fraction(2, true), fraction(1, true)),
+        "west" => (fraction(2, false), fraction(1, true)),
+        "up" => (fraction(0, false), fraction(2, false)),
+        "down" => (fraction(0, false), fraction(2, true)),
+        "south" => (fraction(0, false), fraction(1, true)),
+        _ => (fraction(0, true), fraction(1, true)),
+    };
+
+    // Face rotation turns the texture clockwise
+    let (s, t) = match face.rotation.rem_euclid(360) {
+        90 => (t, 1.0 - s),
+        180 => (1.0 - s, 1.0 - t),
+        270 => (1.0 - t, s),
+        _ => (s, t),
+    };
+
+    let [u1, v1, u2, v2] = face.uv.unwrap_or_else(|| default_uv(element, face_index));
+    [u1 + s * (u2 - u1), v1 + t * (v2 - v1)]
+}
+
+/// UV rectangle the game derives from the element position when a face has none.
+fn default_uv(element: &Element, face_index: usize) -> [f32; 4] {
+    let ([x1, y1, z1], [x2, y2, z2]) = (element.from, element.to);
+
+    match FACE_NAMES[face_index] {
+        "east" => [16.0 - z2, 16.0 - y2, 16.0 - z1, 16.0 - y1],
+        "west" => [z1, 16.0 - y2, z2, 16.0 - y1],
+        "up" => [x1, z1, x2, z2],
+        "down" => [x1, 16.0 - z2, x2, 16.0 - z1],
+        "south" => [x1, 16.0 - y2, x2, 16.0 - y1],
+        _ => [16.0 - x2, 16.0 - y2, 16.0 - x1, 16.0 - y1],
+    }
+}
+
+/// Maps a point of the block into the unrotated space of an element.
+fn to_element_space(rotation: Option<&ElementRotation>, p: [f32; 3]) -> [f32; 3] {
+    let Some(r) = rotation else {
+        return p;
+    };
+
+    let mut q = [0, 1, 2].map(|a| p[a] - r.origin[a]);
+    if r.rescale {
+        let scale = 1.0 / r.angle.to_radians().cos().abs();
+        for (a, c) in q.iter_mut().enumerate() {
+            if a != axis_index(&r.axis) {
+                *c /= scale;
+            }
+        }
+    }
+
+    let q = rotate_vector(r, q, -1.0);
+    [0, 1, 2].map(|a| q[a] + r.origin[a])
+}
+
+/// Rotates `v` about the axis of `r` by its angle times `sign`, right-handed.
+fn rotate_vector(r: &ElementRotation, v: [f32; 3], sign: f32) -> [f32; 3] {
+    let (sin, cos) = (sign * r.angle).to_radians().sin_cos();
+    let [x, y, z] = v;
+
+    match r.axis {
+        Axis::X => [x, y * cos - z * sin, y * sin + z * cos],
+        Axis::Y => [x * cos + z * sin, y, -x * sin + z * cos],
+        Axis::Z => [x * cos - y * sin, x * sin + y * cos, z],
+    }
+}
+
+fn axis_index(axis: &Axis) -> usize {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    }
+}
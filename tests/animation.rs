@@ -0,0 +1,372 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{MemorySource, image};
+use minecraft_blocks::{
+    Diagnostics, Error,
+    animation::{Animation, Animations, MAX_ANIMATION_FRAMES},
+    palette::BlockTexture,
+    schema::location::ResourceLocation,
+};
+use serde_json::json;
+
+/// Opaque gray of `value`.
+fn gray(value: u8) -> [u8; 4] {
+    [value, value, value, 255]
+}
+
+/// Frames of 16×16 pixels stacked vertically, each a gray of `values`.
+fn stacked(values: &[u8]) -> image::RgbaImage {
+    image(16, 16 * values.len() as u32, |_, y| {
+        gray(values[y as usize / 16])
+    })
+}
+
+fn animated(source: MemorySource, texture: &str, meta: serde_json::Value) -> MemorySource {
+    source.with_file(
+        &format!("assets/minecraft/textures/{}.png.mcmeta", texture),
+        meta.to_string(),
+    )
+}
+
+fn location(texture: &str) -> ResourceLocation {
+    texture.parse().unwrap()
+}
+
+/// Frame paths and ticks of `animation`.
+fn frames(animation: &Animation) -> Vec<(String, u32)> {
+    animation
+        .frames
+        .iter()
+        .map(|(name, ticks)| (name.path.clone(), *ticks))
+        .collect()
+}
+
+/// Gray of the top left pixel of `frame`.
+fn frame_gray(animations: &Animations, frame: &ResourceLocation) -> u8 {
+    animations.image(frame).unwrap().get_pixel(0, 0)[0]
+}
+
+#[test]
+fn frames_play_in_keyframe_order_with_their_own_time() {
+    let source = animated(
+        MemorySource::default().with_texture("block/a", &stacked(&[10, 20, 30])),
+        "block/a",
+        json!({"animation": {"frametime": 3, "frames": [2, 0, {"index": 1, "time": 5}, 2]}}),
+    );
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+
+    let animation = animations
+        .get(&location("block/a"), &source, &mut diagnostics)
+        .unwrap()
+        .clone();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    // Frames are named in order of first appearance, so repeated keyframes share a name
+    assert_eq!(
+        frames(&animation),
+        [
+            ("block/a/0".to_owned(), 3),
+            ("block/a/1".to_owned(), 3),
+            ("block/a/2".to_owned(), 5),
+            ("block/a/0".to_owned(), 3),
+        ]
+    );
+    let grays: Vec<u8> = animation
+        .frames
+        .iter()
+        .map(|(name, _)| frame_gray(&animations, name))
+        .collect();
+    assert_eq!(grays, [30, 10, 20, 30]);
+
+    assert_eq!(animation.ticks(), 14);
+    assert_eq!(animation.unit(), 1);
+    assert_eq!(animation.frame_at(0).path, "block/a/0");
+    assert_eq!(animation.frame_at(5).path, "block/a/1");
+    assert_eq!(animation.frame_at(6).path, "block/a/2");
+    assert_eq!(animation.frame_at(11).path, "block/a/0");
+    assert_eq!(animation.frame_at(14 + 6).path, "block/a/2");
+}
+
+#[test]
+fn textures_without_animation_are_static() {
+    let source = MemorySource::default()
+        .with_texture("block/a", &stacked(&[10, 20]))
+        .with_texture("block/b", &stacked(&[10, 20]))
+        .with_file("assets/minecraft/textures/block/b.png.mcmeta", "{}");
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+
+    assert!(
+        animations
+            .get(&location("block/a"), &source, &mut diagnostics)
+            .is_none()
+    );
+    assert!(
+        animations
+            .get(&location("block/b"), &source, &mut diagnostics)
+            .is_none()
+    );
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn interpolation_blends_into_the_next_keyframe() {
+    let source = animated(
+        MemorySource::default().with_texture("block/a", &stacked(&[0, 200])),
+        "block/a",
+        json!({"animation": {"frametime": 4, "interpolate": true}}),
+    );
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+
+    let animation = animations
+        .get(&location("block/a"), &source, &mut diagnostics)
+        .unwrap()
+        .clone();
+
+    // 4 ticks divide into 4 steps of 1 tick, each frame blending a quarter further
+    assert_eq!(animation.frames.len(), 8);
+    assert!(animation.frames.iter().all(|(_, ticks)| *ticks == 1));
+    let grays: Vec<u8> = animation
+        .frames
+        .iter()
+        .map(|(name, _)| frame_gray(&animations, name))
+        .collect();
+    assert_eq!(grays, [0, 50, 100, 150, 200, 150, 100, 50]);
+}
+
+#[test]
+fn interpolation_steps_divide_the_frame_time() {
+    for (frametime, steps) in [(1, 1), (6, 6), (7, 7), (9, 3), (16, 8), (11, 1)] {
+        let source = animated(
+            MemorySource::default().with_texture("block/a", &stacked(&[0, 200])),
+            "block/a",
+            json!({"animation": {"frametime": frametime, "interpolate": true}}),
+        );
+        let animation = Animations::default()
+            .get(&location("block/a"), &source, &mut Diagnostics::default())
+            .unwrap()
+            .clone();
+
+        assert_eq!(animation.frames.len(), 2 * steps, "frametime {}", frametime);
+        assert_eq!(
+            animation.ticks(),
+            2 * frametime as u64,
+            "frametime {}",
+            frametime
+        );
+    }
+}
+
+#[test]
+fn frames_tile_the_image() {
+    // Square frames side by side, in rows
+    let source = animated(
+        MemorySource::default().with_texture(
+            "block/a",
+            &image(32, 32, |x, y| gray((x / 16 + 2 * (y / 16)) as u8 * 10)),
+        ),
+        "block/a",
+        json!({"animation": {"width": 16, "height": 16}}),
+    );
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+    let animation = animations
+        .get(&location("block/a"), &source, &mut diagnostics)
+        .unwrap()
+        .clone();
+    let grays: Vec<u8> = animation
+        .frames
+        .iter()
+        .map(|(name, _)| frame_gray(&animations, name))
+        .collect();
+    assert_eq!(grays, [0, 10, 20, 30]);
+
+    // Without a size, frames are square and as wide as the image is narrow
+    let source = animated(
+        MemorySource::default().with_texture("block/b", &image(48, 16, |x, _| gray(x as u8))),
+        "block/b",
+        json!({"animation": {}}),
+    );
+    let animation = animations
+        .get(&location("block/b"), &source, &mut diagnostics)
+        .unwrap()
+        .clone();
+    let first_columns: Vec<u8> = animation
+        .frames
+        .iter()
+        .map(|(name, _)| frame_gray(&animations, name))
+        .collect();
+    assert_eq!(first_columns, [0, 16, 32]);
+    assert_eq!(
+        animations
+            .image(&animation.frames[0].0)
+            .unwrap()
+            .dimensions(),
+        (16, 16)
+    );
+
+    // Only width given leaves frames as high as the image
+    let source = animated(
+        MemorySource::default().with_texture("block/c", &image(16, 32, |x, _| gray(x as u8))),
+        "block/c",
+        json!({"animation": {"width": 8}}),
+    );
+    let animation = animations
+        .get(&location("block/c"), &source, &mut diagnostics)
+        .unwrap()
+        .clone();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    let first_columns: Vec<u8> = animation
+        .frames
+        .iter()
+        .map(|(name, _)| frame_gray(&animations, name))
+        .collect();
+    assert_eq!(first_columns, [0, 8]);
+    assert_eq!(
+        animations
+            .image(&animation.frames[0].0)
+            .unwrap()
+            .dimensions(),
+        (8, 32)
+    );
+}
+
+#[test]
+fn invalid_animations_are_reported_and_static() {
+    let cases = [
+        json!({"animation": {"width": 12}}),
+        json!({"animation": {"frames": []}}),
+        json!({"animation": {"frames": [0, 2]}}),
+        json!({"animation": {"frametime": 0}}),
+    ];
+    for meta in cases {
+        let source = animated(
+            MemorySource::default().with_texture("block/a", &stacked(&[0, 200])),
+            "block/a",
+            meta.clone(),
+        );
+        let mut diagnostics = Diagnostics::default();
+
+        assert!(
+            Animations::default()
+                .get(&location("block/a"), &source, &mut diagnostics)
+                .is_none(),
+            "{}",
+            meta
+        );
+        assert!(
+            matches!(diagnostics.errors(), [Error::InvalidAnimation { .. }]),
+            "{}: {:?}",
+            meta,
+            diagnostics.errors()
+        );
+    }
+}
+
+fn block_texture(faces: [&str; 6]) -> BlockTexture {
+    let [x, nx, y, ny, z, nz] = faces;
+    serde_json::from_value(json!({"x": x, "nx": nx, "y": y, "ny": ny, "z": z, "nz": nz})).unwrap()
+}
+
+#[test]
+fn faces_play_their_animations_together() {
+    let source = MemorySource::default()
+        .with_texture("block/a", &stacked(&[0, 1]))
+        .with_texture("block/b", &stacked(&[0, 1, 2]))
+        .with_texture("block/c", &stacked(&[0]));
+    let source = animated(source, "block/a", json!({"animation": {"frametime": 2}}));
+    let source = animated(source, "block/b", json!({"animation": {"frametime": 4}}));
+    let texture = block_texture([
+        "block/a", "block/a", "block/b", "block/b", "block/c", "block/c",
+    ]);
+
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+    let (frames, delay) = animations
+        .animate(&texture, &source, &mut diagnostics)
+        .unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    // Loops of 4 and 12 ticks repeat together every 12 ticks, in steps of 2
+    assert_eq!(delay, Duration::from_millis(100));
+    assert_eq!(frames.len(), 6);
+    let paths = |face: fn(&BlockTexture) -> &ResourceLocation| -> Vec<String> {
+        frames.iter().map(|f| face(f).path.clone()).collect()
+    };
+    assert_eq!(
+        paths(|t| &t.x.path),
+        [
+            "block/a/0",
+            "block/a/1",
+            "block/a/0",
+            "block/a/1",
+            "block/a/0",
+            "block/a/1"
+        ]
+    );
+    assert_eq!(
+        paths(|t| &t.y.path),
+        [
+            "block/b/0",
+            "block/b/0",
+            "block/b/1",
+            "block/b/1",
+            "block/b/2",
+            "block/b/2"
+        ]
+    );
+    assert!(paths(|t| &t.z.path).iter().all(|p| p == "block/c"));
+
+    let static_texture = block_texture(["block/c"; 6]);
+    assert!(
+        animations
+            .animate(&static_texture, &source, &mut diagnostics)
+            .is_none()
+    );
+}
+
+#[test]
+fn too_many_frames_are_reported_and_cut_to_the_first() {
+    // Loops of 17 and 19 ticks only repeat together after 323 ticks
+    let source = MemorySource::default()
+        .with_texture("block/a", &stacked(&[0, 1]))
+        .with_texture("block/b", &stacked(&[0, 1]));
+    let source = animated(
+        source,
+        "block/a",
+        json!({"animation": {"frames": [{"index": 0, "time": 1}, {"index": 1, "time": 16}]}}),
+    );
+    let source = animated(
+        source,
+        "block/b",
+        json!({"animation": {"frames": [{"index": 0, "time": 1}, {"index": 1, "time": 18}]}}),
+    );
+    let texture = block_texture([
+        "block/a", "block/b", "block/a", "block/b", "block/a", "block/b",
+    ]);
+
+    let mut animations = Animations::default();
+    let mut diagnostics = Diagnostics::default();
+    let (frames, _) = animations
+        .animate(&texture, &source, &mut diagnostics)
+        .unwrap();
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].x.path.path, "block/a/0");
+    assert_eq!(frames[0].nx.path.path, "block/b/0");
+    let [
+        Error::TooManyAnimationFrames {
+            textures,
+            max_frames,
+        },
+    ] = diagnostics.errors()
+    else {
+        panic!("{:?}", diagnostics.errors());
+    };
+    assert_eq!(*textures, [location("block/a"), location("block/b")]);
+    assert_eq!(*max_frames, MAX_ANIMATION_FRAMES);
+}
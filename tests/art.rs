@@ -0,0 +1,183 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{cube, image, palette};
+use minecraft_blocks::{
+    art::{BlockArt, Dither, Facing, block_art},
+    matching::{ColorIndex, DeltaE, MatchFilter},
+    palette::{Color, Palette},
+};
+
+fn black_and_white() -> Palette {
+    palette(vec![
+        ("black", cube("black", Color::rgb(0x000000))),
+        ("white", cube("white", Color::rgb(0xffffff))),
+    ])
+}
+
+fn index(palette: &Palette) -> ColorIndex {
+    palette.color_index(DeltaE::Ok, &MatchFilter::default())
+}
+
+fn gray(width: u32, height: u32, value: u8) -> image::RgbaImage {
+    image(width, height, |_, _| [value, value, value, 255])
+}
+
+fn count(art: &BlockArt, id: &str) -> usize {
+    art.rows
+        .iter()
+        .flatten()
+        .filter(|cell| cell.as_deref() == Some(id))
+        .count()
+}
+
+#[test]
+fn every_pixel_gets_its_nearest_block_without_dithering() {
+    let palette = black_and_white();
+    let art = block_art(
+        &image(2, 2, |x, _| {
+            if x == 0 {
+                [20, 20, 20, 255]
+            } else {
+                [230, 230, 230, 255]
+            }
+        }),
+        &index(&palette),
+        Facing::South,
+        Dither::None,
+    );
+
+    assert_eq!((art.width, art.height, art.facing), (2, 2, Facing::South));
+    let row = vec![Some("black".to_owned()), Some("white".to_owned())];
+    assert_eq!(art.rows, [row.clone(), row]);
+
+    let art = block_art(
+        &gray(8, 8, 128),
+        &index(&palette),
+        Facing::South,
+        Dither::None,
+    );
+    assert_eq!(count(&art, "white"), 64);
+}
+
+#[test]
+fn transparent_pixels_and_empty_indices_place_nothing() {
+    let palette = black_and_white();
+    let art = block_art(
+        &image(2, 1, |x, _| [0, 0, 0, if x == 0 { 127 } else { 128 }]),
+        &index(&palette),
+        Facing::South,
+        Dither::FloydSteinberg,
+    );
+    assert_eq!(art.rows, [[None, Some("black".to_owned())]]);
+
+    let empty = black_and_white().color_index(
+        DeltaE::Ok,
+        &MatchFilter {
+            groups: vec!["missing".to_owned()],
+            ..Default::default()
+        },
+    );
+    let art = block_art(&gray(3, 2, 128), &empty, Facing::Up, Dither::Ordered);
+    assert_eq!(art.rows, vec![vec![None; 3]; 2]);
+}
+
+#[test]
+fn floyd_steinberg_mixes_blocks_in_proportion() {
+    let palette = black_and_white();
+    let art = block_art(
+        &gray(32, 32, 128),
+        &index(&palette),
+        Facing::South,
+        Dither::FloydSteinberg,
+    );
+
+    // 128 is about half of 255, so about half of the blocks are white
+    let white = count(&art, "white");
+    assert!((461..=563).contains(&white), "{} white blocks", white);
+    assert_eq!(white + count(&art, "black"), 32 * 32);
+
+    // The first pixel is closer to white, and the error it leaves makes the next black
+    assert_eq!(art.rows[0][0].as_deref(), Some("white"));
+    assert_eq!(art.rows[0][1].as_deref(), Some("black"));
+
+    // Darker grays get fewer white blocks
+    let dark = block_art(
+        &gray(32, 32, 64),
+        &index(&palette),
+        Facing::South,
+        Dither::FloydSteinberg,
+    );
+    assert!(count(&dark, "white") < white / 2);
+}
+
+#[test]
+fn ordered_dithering_repeats_every_four_pixels() {
+    let palette = black_and_white();
+    // Gray about halfway between black and white in OKLab lightness
+    let art = block_art(
+        &gray(8, 8, 99),
+        &index(&palette),
+        Facing::South,
+        Dither::Ordered,
+    );
+
+    let tile = |x0: usize, y0: usize| -> Vec<Vec<Option<String>>> {
+        art.rows[y0..y0 + 4]
+            .iter()
+            .map(|row| row[x0..x0 + 4].to_vec())
+            .collect()
+    };
+    assert_eq!(tile(0, 0), tile(4, 0));
+    assert_eq!(tile(0, 0), tile(0, 4));
+    assert_eq!(tile(0, 0), tile(4, 4));
+
+    let white = count(&art, "white");
+    assert!(white > 0 && white < 64, "{} white blocks", white);
+
+    // Flat areas far from the boundary between two blocks stay flat
+    let light = block_art(
+        &gray(8, 8, 200),
+        &index(&palette),
+        Facing::South,
+        Dither::Ordered,
+    );
+    assert_eq!(count(&light, "white"), 64);
+}
+
+#[test]
+fn facings_place_the_image_as_seen_by_the_viewer() {
+    let (width, height) = (3, 2);
+    // Positions of the top left, top right and bottom left pixels
+    let corners = [(0, 0), (2, 0), (0, 1)];
+
+    for (facing, size, expected) in [
+        (Facing::Up, [3, 1, 2], [[0, 0, 0], [2, 0, 0], [0, 0, 1]]),
+        (Facing::Down, [3, 1, 2], [[0, 0, 1], [2, 0, 1], [0, 0, 0]]),
+        (Facing::North, [3, 2, 1], [[2, 1, 0], [0, 1, 0], [2, 0, 0]]),
+        (Facing::South, [3, 2, 1], [[0, 1, 0], [2, 1, 0], [0, 0, 0]]),
+        (Facing::East, [1, 2, 3], [[0, 1, 2], [0, 1, 0], [0, 0, 2]]),
+        (Facing::West, [1, 2, 3], [[0, 1, 0], [0, 1, 2], [0, 0, 0]]),
+    ] {
+        assert_eq!(facing.size(width, height), size, "{:?}", facing);
+        assert_eq!(
+            corners.map(|(column, row)| facing.position(column, row, width, height)),
+            expected,
+            "{:?}",
+            facing
+        );
+
+        // Every pixel gets its own position inside the size
+        let positions: BTreeSet<[usize; 3]> = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (column, row)))
+            .map(|(column, row)| facing.position(column, row, width, height))
+            .collect();
+        assert_eq!(positions.len(), width * height, "{:?}", facing);
+        assert!(
+            positions.iter().all(|p| (0..3).all(|i| p[i] < size[i])),
+            "{:?}",
+            facing
+        );
+    }
+}
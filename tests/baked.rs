@@ -0,0 +1,251 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::MemorySource;
+use minecraft_blocks::{
+    AssetStore, Diagnostics,
+    baked::BakedModel,
+    schema::{
+        location::ResourceLocation,
+        model::{Model, TextureRef},
+    },
+    source::LayeredSource,
+};
+
+fn models(json: &[(&str, &str)]) -> HashMap<ResourceLocation, Model> {
+    json.iter()
+        .map(|(name, model)| (name.parse().unwrap(), serde_json::from_str(model).unwrap()))
+        .collect()
+}
+
+fn load(source: MemorySource, diagnostics: &mut Diagnostics) -> AssetStore {
+    let mut layers = LayeredSource::default();
+    layers.push("models".to_owned(), Box::new(source));
+    AssetStore::load(layers, diagnostics).unwrap()
+}
+
+#[test]
+fn builtin_parents_end_the_chain() {
+    let source = MemorySource::default()
+        .with_file(
+            "assets/minecraft/models/item/generated.json",
+            r#"{"parent": "builtin/generated"}"#,
+        )
+        .with_file(
+            "assets/minecraft/models/item/stick.json",
+            r#"{"parent": "minecraft:item/generated", "textures": {"layer0": "item/stick"}}"#,
+        )
+        .with_file(
+            "assets/minecraft/models/block/chest.json",
+            r#"{"parent": "builtin/entity", "textures": {"particle": "block/oak_planks"}}"#,
+        );
+    let mut diagnostics = Diagnostics::default();
+    load(source, &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    // The chain keeps everything up to the built-in parent
+    let models = models(&[
+        ("item/generated", r#"{"parent": "builtin/generated"}"#),
+        (
+            "item/stick",
+            r#"{"parent": "item/generated", "textures": {"layer0": "item/stick"}}"#,
+        ),
+    ]);
+    let stick =
+        BakedModel::bake(&"item/stick".parse().unwrap(), &models, &mut diagnostics).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    assert_eq!(
+        stick.textures["layer0"],
+        TextureRef::Location("item/stick".parse().unwrap())
+    );
+
+    // Other missing parents are still reported
+    let source = MemorySource::default().with_file(
+        "assets/minecraft/models/block/broken.json",
+        r#"{"parent": "block/missing"}"#,
+    );
+    let mut diagnostics = Diagnostics::default();
+    load(source, &mut diagnostics);
+    assert_eq!(
+        diagnostics
+            .errors()
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>(),
+        ["Model minecraft:block/missing referenced by minecraft:block/broken not found"]
+    );
+}
+
+/// `block/block` ← `block/cube` ← `block/cube_all` ← `block/glass` ← `block/tinted_glass`,
+/// like the vanilla chain, and `block/slab` ← `block/cube_all` replacing the elements.
+fn chain() -> HashMap<ResourceLocation, Model> {
+    models(&[
+        (
+            "block/block",
+            r#"{"display": {
+                "gui": {"rotation": [30, 225, 0], "scale": [0.625, 0.625, 0.625]},
+                "ground": {"scale": [0.25, 0.25, 0.25]}
+            }}"#,
+        ),
+        (
+            "block/cube",
+            r##"{"parent": "block/block", "textures": {"particle": "#north"}, "elements": [{
+                "from": [0, 0, 0],
+                "to": [16, 16, 16],
+                "faces": {"north": {"texture": "#north"}, "up": {"texture": "#up"}}
+            }]}"##,
+        ),
+        (
+            "block/cube_all",
+            r##"{"parent": "block/cube", "textures": {"north": "#all", "up": "#all"}}"##,
+        ),
+        (
+            "block/glass",
+            r#"{"parent": "block/cube_all", "ambientocclusion": false,
+                "textures": {"all": "block/glass"},
+                "display": {"gui": {"scale": [0.5, 0.5, 0.5]}}}"#,
+        ),
+        (
+            "block/tinted_glass",
+            r#"{"parent": "block/glass", "textures": {"up": "block/tinted_glass"}}"#,
+        ),
+        (
+            "block/slab",
+            r##"{"parent": "block/cube_all", "textures": {"all": "block/oak_planks"}, "elements": [{
+                "from": [0, 0, 0],
+                "to": [16, 8, 16],
+                "faces": {"up": {"texture": "#up"}}
+            }]}"##,
+        ),
+    ])
+}
+
+fn bake(name: &str, models: &HashMap<ResourceLocation, Model>) -> BakedModel {
+    let mut diagnostics = Diagnostics::default();
+    let baked = BakedModel::bake(&name.parse().unwrap(), models, &mut diagnostics).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    baked
+}
+
+fn location(texture: &str) -> TextureRef {
+    TextureRef::Location(texture.parse().unwrap())
+}
+
+/// Texture of each face of the first element, sorted by face.
+fn face_textures(baked: &BakedModel) -> Vec<(&str, TextureRef)> {
+    let mut faces: Vec<(&str, TextureRef)> = baked.elements[0]
+        .faces
+        .iter()
+        .map(|(face, f)| (face.as_str(), f.texture.clone()))
+        .collect();
+    faces.sort_by_key(|(face, _)| *face);
+    faces
+}
+
+#[test]
+fn children_override_textures_of_every_parent() {
+    let models = chain();
+
+    // Variables set at any level resolve through those of the child
+    let glass = bake("block/glass", &models);
+    assert_eq!(glass.textures["all"], location("block/glass"));
+    assert_eq!(glass.textures["particle"], location("block/glass"));
+    assert_eq!(
+        face_textures(&glass),
+        [
+            ("north", location("block/glass")),
+            ("up", location("block/glass"))
+        ]
+    );
+
+    // Two levels down, a child overrides a variable another parent defined
+    let tinted = bake("block/tinted_glass", &models);
+    assert_eq!(
+        face_textures(&tinted),
+        [
+            ("north", location("block/glass")),
+            ("up", location("block/tinted_glass"))
+        ]
+    );
+    // The parent itself is unchanged
+    assert_eq!(
+        bake("block/glass", &models).textures["up"],
+        location("block/glass")
+    );
+}
+
+#[test]
+fn elements_of_the_closest_model_replace_the_parents() {
+    let models = chain();
+
+    let glass = bake("block/glass", &models);
+    assert_eq!(glass.elements.len(), 1);
+    assert_eq!(glass.elements[0].to, [16.0, 16.0, 16.0]);
+    assert!(glass.is_full_cube());
+
+    // The slab's own elements win over those of `block/cube`, not merged with them
+    let slab = bake("block/slab", &models);
+    assert_eq!(slab.elements.len(), 1);
+    assert_eq!(slab.elements[0].to, [16.0, 8.0, 16.0]);
+    assert_eq!(face_textures(&slab), [("up", location("block/oak_planks"))]);
+    assert!(!slab.is_full_cube());
+
+    // Without elements anywhere in the chain there are none
+    assert!(bake("block/block", &models).elements.is_empty());
+}
+
+#[test]
+fn ambient_occlusion_and_display_are_inherited() {
+    let models = chain();
+
+    // Unset anywhere means on
+    assert!(bake("block/cube_all", &models).ambientocclusion);
+    // Turned off by a parent stays off for its children
+    assert!(!bake("block/glass", &models).ambientocclusion);
+    assert!(!bake("block/tinted_glass", &models).ambientocclusion);
+
+    // Transforms merge per display slot, a child replacing a whole slot
+    let tinted = bake("block/tinted_glass", &models);
+    let gui = &tinted.display["gui"];
+    assert_eq!(gui.scale, Some([0.5, 0.5, 0.5]));
+    assert_eq!(gui.rotation, None);
+    assert_eq!(tinted.display["ground"].scale, Some([0.25, 0.25, 0.25]));
+    assert_eq!(
+        bake("block/cube_all", &models).display["gui"].rotation,
+        Some([30.0, 225.0, 0.0])
+    );
+}
+
+#[test]
+fn parent_cycles_deep_in_the_chain_are_detected() {
+    let mut models = chain();
+    models.extend(self::models(&[
+        (
+            "block/looped",
+            r#"{"parent": "block/loop_a", "textures": {"all": "block/stone"}}"#,
+        ),
+        ("block/loop_a", r#"{"parent": "block/loop_b"}"#),
+        (
+            "block/loop_b",
+            r#"{"parent": "block/loop_a", "ambientocclusion": false}"#,
+        ),
+    ]));
+
+    let mut diagnostics = Diagnostics::default();
+    let looped =
+        BakedModel::bake(&"block/looped".parse().unwrap(), &models, &mut diagnostics).unwrap();
+    assert_eq!(
+        diagnostics
+            .errors()
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "Model parents form a cycle: minecraft:block/loop_a -> minecraft:block/loop_b -> minecraft:block/loop_a"
+        ]
+    );
+    // What the chain had before the cycle is still merged
+    assert_eq!(looped.textures["all"], location("block/stone"));
+    assert!(!looped.ambientocclusion);
+}
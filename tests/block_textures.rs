@@ -0,0 +1,96 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::image;
+use minecraft_blocks::{
+    Error,
+    palette::{FaceTexture, Rotation},
+    schema::blockstate::BlockState,
+    textures::{get_block_textures, render_face},
+};
+
+#[test]
+fn missing_blockstate_is_an_error() {
+    let result = get_block_textures(
+        &"minecraft:stone".parse().unwrap(),
+        "",
+        &HashMap::new(),
+        &HashMap::new(),
+        None,
+    );
+
+    assert!(matches!(
+        result,
+        Err(Error::MissingBlockstate { block }) if block.to_string() == "minecraft:stone"
+    ));
+}
+
+#[test]
+fn missing_variant_is_an_error() {
+    let blockstate: BlockState =
+        serde_json::from_str(r#"{"variants": {"axis=y": {"model": "block/oak_log"}}}"#).unwrap();
+    let blockstates = HashMap::from([("minecraft:oak_log".parse().unwrap(), blockstate)]);
+
+    let result = get_block_textures(
+        &"minecraft:oak_log".parse().unwrap(),
+        "axis=x",
+        &HashMap::new(),
+        &blockstates,
+        None,
+    );
+
+    let error = result.unwrap_err();
+    assert!(matches!(&error, Error::MissingVariant { key, .. } if key == "axis=x"));
+    assert_eq!(
+        error.to_string(),
+        r#"Blockstate of block minecraft:oak_log has no variant "axis=x""#
+    );
+}
+
+#[test]
+fn missing_model_is_an_error() {
+    let blockstate: BlockState =
+        serde_json::from_str(r#"{"variants": {"": {"model": "block/stone"}}}"#).unwrap();
+    let blockstates = HashMap::from([("minecraft:stone".parse().unwrap(), blockstate)]);
+
+    let result = get_block_textures(
+        &"minecraft:stone".parse().unwrap(),
+        "",
+        &HashMap::new(),
+        &blockstates,
+        None,
+    );
+
+    assert!(matches!(result, Err(Error::MissingModel { .. })));
+}
+
+#[test]
+fn render_face_crops_mirrors_and_turns() {
+    // Red counts columns and green counts rows
+    let texture = image(4, 4, |x, y| [x as u8, y as u8, 0, 255]);
+    let pixel = |x: u8, y: u8| image::Rgba([x, y, 0, 255]);
+
+    let mut face =
+        FaceTexture::new("block/test".parse().unwrap()).with_uv(Some([8.0, 0.0, 0.0, 8.0]));
+    let pixels = render_face(&face, &texture);
+    assert_eq!(pixels.dimensions(), (2, 2));
+    assert_eq!(*pixels.get_pixel(0, 0), pixel(1, 0));
+    assert_eq!(*pixels.get_pixel(1, 1), pixel(0, 1));
+
+    // Turned counter-clockwise, the right column comes up on top
+    face.rotation = Rotation::CCW90;
+    let pixels = render_face(&face, &texture);
+    assert_eq!(*pixels.get_pixel(0, 0), pixel(0, 0));
+    assert_eq!(*pixels.get_pixel(1, 0), pixel(0, 1));
+    assert_eq!(*pixels.get_pixel(0, 1), pixel(1, 0));
+    assert_eq!(*pixels.get_pixel(1, 1), pixel(1, 1));
+
+    // A degenerate region still shows the pixel it starts at
+    face = face.with_uv(Some([12.0, 4.0, 12.0, 4.0]));
+    face.rotation = Rotation::CCW0;
+    assert_eq!(face.uv_pixels(4, 4), (3..4, 1..2));
+    let pixels = render_face(&face, &texture);
+    assert_eq!(pixels.dimensions(), (1, 1));
+    assert_eq!(*pixels.get_pixel(0, 0), pixel(3, 1));
+}
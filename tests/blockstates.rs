@@ -0,0 +1,305 @@
+mod common;
+
+use common::MemorySource;
+use minecraft_blocks::{
+    AssetStore, Diagnostics,
+    schema::blockstate::{BlockState, ModelVariant, parse_state},
+    source::LayeredSource,
+};
+use serde_json::json;
+
+fn blockstate(json: serde_json::Value) -> BlockState {
+    serde_json::from_value(json).unwrap()
+}
+
+/// Model and y rotation of every model of `parts`, one list per part.
+fn models(parts: &[&ModelVariant]) -> Vec<Vec<(String, i32)>> {
+    parts
+        .iter()
+        .map(|part| {
+            part.models()
+                .iter()
+                .map(|m| (m.model.path.clone(), m.y))
+                .collect()
+        })
+        .collect()
+}
+
+/// Models `blockstate` applies in the state given as a variant key.
+fn applied(blockstate: &BlockState, state: &str) -> Vec<Vec<(String, i32)>> {
+    models(&blockstate.applied_models(&parse_state(state)))
+}
+
+fn single(model: &str, y: i32) -> Vec<(String, i32)> {
+    vec![(model.to_owned(), y)]
+}
+
+fn fence() -> BlockState {
+    blockstate(json!({"multipart": [
+        {"apply": {"model": "block/oak_fence_post"}},
+        {"when": {"north": "true"}, "apply": {"model": "block/oak_fence_side", "uvlock": true}},
+        {"when": {"east": "true"}, "apply": {"model": "block/oak_fence_side", "y": 90, "uvlock": true}},
+        {"when": {"south": "true"}, "apply": {"model": "block/oak_fence_side", "y": 180, "uvlock": true}},
+        {"when": {"west": "true"}, "apply": {"model": "block/oak_fence_side", "y": 270, "uvlock": true}},
+    ]}))
+}
+
+#[test]
+fn variants_apply_the_first_matching_key() {
+    let slab = blockstate(json!({"variants": {
+        "type=bottom": {"model": "block/oak_slab"},
+        "type=double": {"model": "block/oak_planks"},
+        "type=top": {"model": "block/oak_slab_top"},
+    }}));
+    assert_eq!(
+        applied(&slab, "type=top,waterlogged=false"),
+        [single("block/oak_slab_top", 0)]
+    );
+    assert!(applied(&slab, "waterlogged=false").is_empty());
+
+    // A key without properties matches every state
+    let stone = blockstate(json!({"variants": {"": [
+        {"model": "block/stone"},
+        {"model": "block/stone_mirrored", "y": 180},
+    ]}}));
+    assert_eq!(
+        applied(&stone, "anything=1"),
+        [vec![
+            ("block/stone".to_owned(), 0),
+            ("block/stone_mirrored".to_owned(), 180)
+        ]]
+    );
+}
+
+#[test]
+fn overlapping_variants_apply_the_first_in_the_file() {
+    // Sorted by key, `lit=true` would come first. Parsed from text, as `json!` sorts keys
+    let furnace: BlockState = serde_json::from_str(
+        r#"{"variants": {
+            "powered=true": {"model": "block/furnace_powered"},
+            "lit=true": {"model": "block/furnace_on"},
+            "": {"model": "block/furnace"}
+        }}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        furnace.variant_key(&parse_state("lit=true,powered=true")),
+        Some("powered=true")
+    );
+    assert_eq!(
+        applied(&furnace, "lit=true,powered=false"),
+        [single("block/furnace_on", 0)]
+    );
+    assert_eq!(
+        applied(&furnace, "lit=false,powered=false"),
+        [single("block/furnace", 0)]
+    );
+
+    // Written back in the same order
+    let json = serde_json::to_string(&furnace).unwrap();
+    let keys: Vec<String> = serde_json::from_str::<BlockState>(&json)
+        .unwrap()
+        .variants
+        .keys()
+        .cloned()
+        .collect();
+    assert_eq!(keys, ["powered=true", "lit=true", ""]);
+}
+
+#[test]
+fn fences_apply_a_side_per_connection() {
+    let fence = fence();
+
+    assert_eq!(
+        applied(&fence, "east=false,north=false,south=false,west=false"),
+        [single("block/oak_fence_post", 0)]
+    );
+    assert_eq!(
+        applied(&fence, "east=true,north=true,south=false,west=true"),
+        [
+            single("block/oak_fence_post", 0),
+            single("block/oak_fence_side", 0),
+            single("block/oak_fence_side", 90),
+            single("block/oak_fence_side", 270),
+        ]
+    );
+    // Properties missing from the state match no condition
+    assert_eq!(
+        applied(&fence, "north=true"),
+        [
+            single("block/oak_fence_post", 0),
+            single("block/oak_fence_side", 0),
+        ]
+    );
+}
+
+#[test]
+fn walls_apply_a_side_per_height() {
+    let wall = blockstate(json!({"multipart": [
+        {"when": {"up": "true"}, "apply": {"model": "block/wall_post"}},
+        {"when": {"north": "low"}, "apply": {"model": "block/wall_side"}},
+        {"when": {"east": "low"}, "apply": {"model": "block/wall_side", "y": 90}},
+        {"when": {"north": "tall"}, "apply": {"model": "block/wall_side_tall"}},
+        {"when": {"east": "tall"}, "apply": {"model": "block/wall_side_tall", "y": 90}},
+    ]}));
+
+    assert_eq!(
+        applied(&wall, "east=tall,north=low,up=false"),
+        [
+            single("block/wall_side", 0),
+            single("block/wall_side_tall", 90),
+        ]
+    );
+    assert_eq!(
+        applied(&wall, "east=none,north=none,up=true"),
+        [single("block/wall_post", 0)]
+    );
+    assert!(applied(&wall, "east=none,north=none,up=false").is_empty());
+}
+
+fn redstone_wire() -> BlockState {
+    blockstate(json!({"multipart": [
+        {
+            "when": {"OR": [
+                {"east": "none", "north": "none", "south": "none", "west": "none"},
+                {"east": "side|up", "north": "side|up"},
+                {"north": "side|up", "west": "side|up"},
+            ]},
+            "apply": {"model": "block/redstone_dust_dot"}
+        },
+        {"when": {"north": "side|up"}, "apply": {"model": "block/redstone_dust_side0"}},
+        {"when": {"east": "side|up"}, "apply": {"model": "block/redstone_dust_side_alt1", "y": 270}},
+        {"when": {"north": "up"}, "apply": {"model": "block/redstone_dust_up"}},
+    ]}))
+}
+
+#[test]
+fn redstone_wire_matches_alternative_values_and_conditions() {
+    let wire = redstone_wire();
+
+    // Unconnected wire is a dot
+    assert_eq!(
+        applied(&wire, "east=none,north=none,power=0,south=none,west=none"),
+        [single("block/redstone_dust_dot", 0)]
+    );
+    // A corner adds the dot to both sides; `side|up` matches either value
+    assert_eq!(
+        applied(&wire, "east=side,north=up,power=15,south=none,west=none"),
+        [
+            single("block/redstone_dust_dot", 0),
+            single("block/redstone_dust_side0", 0),
+            single("block/redstone_dust_side_alt1", 270),
+            single("block/redstone_dust_up", 0),
+        ]
+    );
+    // A straight line matches none of the alternatives
+    assert_eq!(
+        applied(&wire, "east=none,north=side,power=0,south=side,west=none"),
+        [single("block/redstone_dust_side0", 0)]
+    );
+}
+
+#[test]
+fn and_conditions_need_every_part() {
+    let corner = blockstate(json!({"multipart": [
+        {
+            "when": {"AND": [{"north": "true"}, {"east": "true|side"}]},
+            "apply": {"model": "block/corner"}
+        },
+    ]}));
+
+    assert_eq!(
+        applied(&corner, "east=side,north=true"),
+        [single("block/corner", 0)]
+    );
+    assert!(applied(&corner, "east=true,north=false").is_empty());
+    assert!(applied(&corner, "east=false,north=true").is_empty());
+    assert!(applied(&corner, "north=true").is_empty());
+}
+
+#[test]
+fn assets_apply_models_of_loaded_blockstates() {
+    let source = MemorySource::default().with_file(
+        "assets/minecraft/blockstates/oak_fence.json",
+        serde_json::to_string(&fence()).unwrap(),
+    );
+    let mut layers = LayeredSource::default();
+    layers.push("fences".to_owned(), Box::new(source));
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::load(layers, &mut diagnostics).unwrap();
+
+    let state = parse_state("east=false,north=false,south=true,west=false");
+    let parts = assets
+        .applied_models(&"minecraft:oak_fence".parse().unwrap(), &state)
+        .unwrap();
+    assert_eq!(
+        models(&parts),
+        [
+            single("block/oak_fence_post", 0),
+            single("block/oak_fence_side", 180),
+        ]
+    );
+    assert!(
+        assets
+            .applied_models(&"minecraft:birch_fence".parse().unwrap(), &state)
+            .is_none()
+    );
+}
+
+#[test]
+fn later_layers_replace_whole_blockstates() {
+    let vanilla = MemorySource::default()
+        .with_file(
+            "assets/minecraft/blockstates/oak_fence.json",
+            serde_json::to_string(&fence()).unwrap(),
+        )
+        .with_file(
+            "assets/minecraft/blockstates/oak_slab.json",
+            r#"{"variants": {
+                "type=bottom": {"model": "block/oak_slab"},
+                "type=top": {"model": "block/oak_slab_top"}
+            }}"#,
+        );
+    let pack = MemorySource::default().with_file(
+        "assets/minecraft/blockstates/oak_slab.json",
+        r#"{"variants": {"type=bottom": {"model": "block/pack_slab"}}}"#,
+    );
+    let mut layers = LayeredSource::default();
+    layers.push("vanilla".to_owned(), Box::new(vanilla));
+    layers.push("pack".to_owned(), Box::new(pack));
+    assert_eq!(
+        layers.provider("assets/minecraft/blockstates/oak_slab.json"),
+        Some("pack")
+    );
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::load(layers, &mut diagnostics).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    let slab = "minecraft:oak_slab".parse().unwrap();
+    assert_eq!(
+        models(
+            &assets
+                .applied_models(&slab, &parse_state("type=bottom"))
+                .unwrap()
+        ),
+        [single("block/pack_slab", 0)]
+    );
+    // Variants only the lower layer lists are gone with the rest of its file
+    assert!(
+        assets
+            .applied_models(&slab, &parse_state("type=top"))
+            .unwrap()
+            .is_empty()
+    );
+
+    // Files no later layer has still come from the lower ones
+    let fence = "minecraft:oak_fence".parse().unwrap();
+    assert_eq!(
+        models(
+            &assets
+                .applied_models(&fence, &parse_state("north=false"))
+                .unwrap()
+        ),
+        [single("block/oak_fence_post", 0)]
+    );
+}
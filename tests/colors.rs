@@ -0,0 +1,214 @@
+mod common;
+
+use indexmap::IndexMap;
+use minecraft_blocks::{
+    Diagnostics, Error,
+    colors::{self, TextureImages},
+    palette::{BlockTexture, Color, FaceTexture, Material, MaterialColors, MaterialDisplay},
+    voxel::VoxelGrid,
+};
+
+use common::{MemorySource, image};
+
+const RED: [u8; 4] = [200, 0, 0, 255];
+const GREEN: [u8; 4] = [0, 200, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 200, 255];
+
+fn color([r, g, b, a]: [u8; 4]) -> Color {
+    Color { r, g, b, a }
+}
+
+fn face(texture: &str) -> FaceTexture {
+    FaceTexture::new(format!("minecraft:block/{}", texture).parse().unwrap())
+}
+
+fn cube(face: FaceTexture) -> BlockTexture {
+    BlockTexture {
+        x: face.clone(),
+        nx: face.clone(),
+        y: face.clone(),
+        ny: face.clone(),
+        z: face.clone(),
+        nz: face,
+    }
+}
+
+fn texture_colors(source: &MemorySource, frames: &[BlockTexture]) -> MaterialColors {
+    let no_frames = IndexMap::new();
+    let mut images = TextureImages::assets(source, &no_frames);
+    colors::texture_colors(frames, &mut images).unwrap()
+}
+
+#[test]
+fn average_weighs_pixels_by_alpha() {
+    let source = MemorySource::default().with_texture(
+        "block/glass",
+        &image(2, 1, |x, _| {
+            if x == 0 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 255, 85]
+            }
+        }),
+    );
+
+    let colors = texture_colors(&source, &[cube(face("glass"))]);
+
+    // Red weighs 1 and blue 1/3, and the pixels are 2/3 opaque on average
+    assert_eq!(colors.average, color([191, 0, 64, 170]));
+    assert_eq!(colors.faces.y, colors.average);
+}
+
+#[test]
+fn transparent_pixels_only_lower_alpha() {
+    let source = MemorySource::default().with_texture(
+        "block/leaves",
+        &image(2, 1, |x, _| {
+            if x == 0 {
+                [0, 255, 0, 0]
+            } else {
+                [200, 100, 50, 255]
+            }
+        }),
+    );
+
+    let colors = texture_colors(&source, &[cube(face("leaves"))]);
+
+    assert_eq!(colors.average, color([200, 100, 50, 128]));
+    assert_eq!(colors.dominant, color([200, 100, 50, 255]));
+}
+
+#[test]
+fn tint_is_multiplied_into_pixels() {
+    let source = MemorySource::default()
+        .with_texture("block/grass", &image(1, 1, |_, _| [255, 255, 255, 255]));
+    let tinted = face("grass").with_tint(Some(Color::rgb(0x804020)));
+
+    let colors = texture_colors(&source, &[cube(tinted)]);
+
+    assert_eq!(colors.average, color([0x80, 0x40, 0x20, 255]));
+}
+
+#[test]
+fn dominant_is_the_heaviest_cluster() {
+    // 9 green, 4 red and 3 blue pixels
+    let source = MemorySource::default().with_texture(
+        "block/mixed",
+        &image(4, 4, |x, y| match y * 4 + x {
+            0..9 => GREEN,
+            9..13 => RED,
+            _ => BLUE,
+        }),
+    );
+
+    let colors = texture_colors(&source, &[cube(face("mixed"))]);
+
+    assert_eq!(colors.dominant, color(GREEN));
+    assert_eq!(colors.average, color([50, 113, 38, 255]));
+}
+
+#[test]
+fn dominant_is_deterministic() {
+    let source = MemorySource::default().with_texture(
+        "block/noise",
+        &image(8, 8, |x, y| {
+            let n = (x * 37 + y * 91) % 64;
+            [n as u8 * 4, 255 - n as u8 * 3, (n * n % 256) as u8, 255]
+        }),
+    );
+    let frames = [cube(face("noise"))];
+
+    let first = texture_colors(&source, &frames);
+    for _ in 0..4 {
+        assert_eq!(texture_colors(&source, &frames), first);
+    }
+}
+
+#[test]
+fn faces_are_cropped_to_their_uv_region() {
+    // Left half red, right half blue
+    let source = MemorySource::default().with_texture(
+        "block/split",
+        &image(4, 4, |x, _| if x < 2 { RED } else { BLUE }),
+    );
+    let region = |uv: [f32; 4]| {
+        let colors = texture_colors(&source, &[cube(face("split").with_uv(Some(uv)))]);
+        colors.average
+    };
+
+    assert_eq!(region([0.0, 0.0, 8.0, 16.0]), color(RED));
+    assert_eq!(region([8.0, 0.0, 16.0, 16.0]), color(BLUE));
+    // Mirrored regions cover the same pixels
+    assert_eq!(region([8.0, 16.0, 0.0, 0.0]), color(RED));
+    // One column, and a degenerate region still covering one pixel
+    assert_eq!(region([12.0, 0.0, 16.0, 16.0]), color(BLUE));
+    assert_eq!(region([4.0, 4.0, 4.0, 4.0]), color(RED));
+    assert_eq!(region([0.0, 0.0, 16.0, 16.0]), color([100, 0, 100, 255]));
+}
+
+#[test]
+fn faces_get_colors_of_their_own() {
+    let source = MemorySource::default()
+        .with_texture("block/red", &image(1, 1, |_, _| RED))
+        .with_texture("block/blue", &image(1, 1, |_, _| BLUE));
+    let texture = BlockTexture {
+        y: face("red"),
+        ..cube(face("blue"))
+    };
+
+    let colors = texture_colors(&source, &[texture]);
+
+    assert_eq!(colors.faces.y, color(RED));
+    assert_eq!(colors.faces.ny, color(BLUE));
+    assert_eq!(colors.faces.x, color(BLUE));
+    assert_eq!(colors.dominant, color(BLUE));
+}
+
+#[test]
+fn frames_are_counted_together() {
+    let source = MemorySource::default()
+        .with_texture("block/red", &image(1, 1, |_, _| RED))
+        .with_texture("block/blue", &image(1, 1, |_, _| BLUE));
+
+    let colors = texture_colors(&source, &[cube(face("red")), cube(face("blue"))]);
+
+    assert_eq!(colors.average, color([100, 0, 100, 255]));
+}
+
+#[test]
+fn unreadable_textures_are_reported() {
+    let source = MemorySource::default().with_texture("block/red", &image(1, 1, |_, _| RED));
+    let material = |texture: &str| Material {
+        display: MaterialDisplay::Texture(cube(face(texture))),
+        profile: None,
+        packs: Vec::new(),
+        colors: None,
+    };
+    let mut materials = IndexMap::from([
+        ("minecraft:red".to_owned(), material("red")),
+        ("minecraft:missing".to_owned(), material("missing")),
+    ]);
+    let no_frames = IndexMap::new();
+    let mut images = TextureImages::assets(&source, &no_frames);
+    let mut diagnostics = Diagnostics::default();
+
+    let colored = colors::add_colors(
+        materials.iter_mut(),
+        &mut images,
+        &mut |path| {
+            Err::<VoxelGrid, _>(Error::MissingVolume {
+                path: path.to_owned(),
+            })
+        },
+        &mut diagnostics,
+    );
+
+    assert_eq!(colored, 1);
+    assert!(materials["minecraft:red"].colors.is_some());
+    assert!(materials["minecraft:missing"].colors.is_none());
+    assert!(matches!(
+        diagnostics.errors(),
+        [Error::MaterialColors { material, source }]
+            if material == "minecraft:missing" && matches!(**source, Error::Io { .. })
+    ));
+}
@@ -0,0 +1,132 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::{collections::BTreeMap, io};
+
+use image::{ImageFormat, RgbaImage};
+use indexmap::IndexMap;
+use minecraft_blocks::{
+    palette::{Color, Material, Palette},
+    source::{AssetSource, join},
+};
+use serde_json::json;
+
+/// Files held in memory, keyed by their `/`-separated path.
+#[derive(Default)]
+pub struct MemorySource {
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl MemorySource {
+    pub fn with_file(mut self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        self.files.insert(path.to_owned(), contents.into());
+        self
+    }
+
+    pub fn with_png(self, path: &str, image: &RgbaImage) -> Self {
+        self.with_file(path, png(image))
+    }
+
+    /// Adds `textures/<texture>.png` to the `minecraft` namespace.
+    pub fn with_texture(self, texture: &str, image: &RgbaImage) -> Self {
+        self.with_png(&format!("assets/minecraft/textures/{}.png", texture), image)
+    }
+}
+
+impl AssetSource for MemorySource {
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = join(dir, "");
+        Ok(self
+            .files
+            .keys()
+            .filter(|p| {
+                p.strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains('/'))
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = join(dir, "");
+        let mut dirs: Vec<String> = self
+            .files
+            .keys()
+            .filter_map(|p| p.strip_prefix(&prefix)?.split_once('/'))
+            .map(|(sub_dir, _)| join(dir, sub_dir))
+            .collect();
+        dirs.dedup();
+        Ok(dirs)
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path.to_owned()))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn describe(&self) -> String {
+        "memory".to_owned()
+    }
+}
+
+pub fn png(image: &RgbaImage) -> Vec<u8> {
+    let mut bytes = io::Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+    bytes.into_inner()
+}
+
+/// Image of `width`×`height` pixels colored by `pixel(x, y)`.
+pub fn image(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| image::Rgba(pixel(x, y)))
+}
+
+/// Material displayed as `display`, with colors `faces` in the order x, nx, y, ny, z, nz
+/// and the average of the first.
+pub fn material(display: serde_json::Value, faces: [Color; 6]) -> Material {
+    let mut material: Material = serde_json::from_value(json!({ "display": display })).unwrap();
+    let [x, nx, y, ny, z, nz] = faces.map(String::from);
+    let average = String::from(faces[0]);
+    material.colors = Some(
+        serde_json::from_value(json!({
+            "average": average,
+            "dominant": average,
+            "faces": { "x": x, "nx": nx, "y": y, "ny": ny, "z": z, "nz": nz },
+        }))
+        .unwrap(),
+    );
+    material
+}
+
+/// Cube of `block/<texture>` on every face, all of `color`.
+pub fn cube(texture: &str, color: Color) -> Material {
+    let face = format!("block/{}", texture);
+    material(
+        json!({ "x": face, "nx": face, "y": face, "ny": face, "z": face, "nz": face }),
+        [color; 6],
+    )
+}
+
+/// Volume at `path`, all of `color`.
+pub fn volume(path: &str, color: Color) -> Material {
+    material(json!({ "path": path }), [color; 6])
+}
+
+/// Palette named `test` of `materials` by id, without groups.
+pub fn palette(materials: Vec<(&str, Material)>) -> Palette {
+    Palette {
+        name: "test".to_owned(),
+        id: "test".to_owned(),
+        materials: materials
+            .into_iter()
+            .map(|(id, m)| (id.to_owned(), m))
+            .collect(),
+        groups: IndexMap::new(),
+        variant_sets: IndexMap::new(),
+    }
+}
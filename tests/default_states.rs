@@ -0,0 +1,60 @@
+use std::process::Command;
+
+use minecraft_blocks::{AssetStore, Diagnostics};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/default_states");
+
+fn assets() -> AssetStore {
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::open(&[FIXTURES], &mut diagnostics).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    assets
+}
+
+fn material_ids(assets: &AssetStore, default_only: bool) -> Vec<String> {
+    let mut diagnostics = Diagnostics::default();
+    let set = assets.full_cube_materials(default_only, &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    let mut ids: Vec<String> = set.materials.keys().cloned().collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn only_default_states_are_kept() {
+    let mut assets = assets();
+    assets
+        .load_blocks_report(format!("{}/blocks.json", FIXTURES))
+        .unwrap();
+
+    assert_eq!(material_ids(&assets, false).len(), 8);
+    assert_eq!(
+        material_ids(&assets, true),
+        [
+            "minecraft:furnace#facing=north,lit=false",
+            "minecraft:oak_log#axis=y",
+            "minecraft:stone",
+        ]
+    );
+}
+
+#[test]
+fn without_a_registry_only_blocks_without_properties_are_default() {
+    let assets = assets();
+
+    assert_eq!(material_ids(&assets, true), ["minecraft:stone"]);
+}
+
+#[test]
+fn default_states_only_needs_a_registry() {
+    let output = Command::new(env!("CARGO_BIN_EXE_minecraft-blocks"))
+        .args(["palette", "--assets", FIXTURES, "--default-states-only"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--java-src"), "{}", stderr);
+    assert!(stderr.contains("--blocks-report"), "{}", stderr);
+}
@@ -0,0 +1,229 @@
+use minecraft_blocks::{AssetStore, Diagnostics, assets::MaterialSet};
+use serde_json::Value;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/rotations");
+
+fn full_blocks() -> (MaterialSet, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::open(&[FIXTURES], &mut diagnostics).unwrap();
+    let set = assets.full_cube_materials(false, &mut diagnostics);
+    (set, diagnostics)
+}
+
+fn messages(diagnostics: &Diagnostics) -> Vec<String> {
+    diagnostics.errors().iter().map(|e| e.to_string()).collect()
+}
+
+/// Face strings of `id` as written to `full_blocks.json`, in `x nx y ny z nz` order.
+fn faces(set: &MaterialSet, id: &str) -> [String; 6] {
+    let material = serde_json::to_value(&set.materials[id]).unwrap();
+    ["x", "nx", "y", "ny", "z", "nz"].map(|face| match &material["display"][face] {
+        Value::String(s) => s.clone(),
+        other => panic!("{} has no face {}: {}", id, face, other),
+    })
+}
+
+#[test]
+fn upright_log_is_not_rotated() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:oak_log#axis=y"),
+        [
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+        ]
+    );
+}
+
+#[test]
+fn log_along_z_turns_up_to_north() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:oak_log#axis=z"),
+        [
+            "minecraft:block/oak_log#r=270",
+            "minecraft:block/oak_log#r=90",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log#r=180",
+            "minecraft:block/oak_log_top#r=180",
+            "minecraft:block/oak_log_top",
+        ]
+    );
+}
+
+#[test]
+fn log_along_x_turns_north_to_east() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:oak_log#axis=x"),
+        [
+            "minecraft:block/oak_log_top#r=180",
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log#r=270",
+            "minecraft:block/oak_log#r=270",
+            "minecraft:block/oak_log#r=90",
+            "minecraft:block/oak_log#r=270",
+        ]
+    );
+}
+
+#[test]
+fn face_rotation_is_applied() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:observer#facing=north"),
+        [
+            "minecraft:block/observer_side",
+            "minecraft:block/observer_side",
+            "minecraft:block/observer_top#r=180",
+            "minecraft:block/observer_top",
+            "minecraft:block/observer_front",
+            "minecraft:block/observer_back",
+        ]
+    );
+}
+
+#[test]
+fn face_rotation_adds_to_blockstate_rotation() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:observer#facing=east"),
+        [
+            "minecraft:block/observer_front",
+            "minecraft:block/observer_back",
+            "minecraft:block/observer_top#r=90",
+            "minecraft:block/observer_top#r=90",
+            "minecraft:block/observer_side",
+            "minecraft:block/observer_side",
+        ]
+    );
+    assert_eq!(
+        faces(&set, "minecraft:observer#facing=down"),
+        [
+            "minecraft:block/observer_side#r=270",
+            "minecraft:block/observer_side#r=90",
+            "minecraft:block/observer_back",
+            "minecraft:block/observer_front#r=180",
+            "minecraft:block/observer_top",
+            "minecraft:block/observer_top",
+        ]
+    );
+}
+
+#[test]
+fn invalid_face_rotation_is_reported() {
+    let (set, diagnostics) = full_blocks();
+
+    assert!(!set.materials.contains_key("minecraft:tilted"));
+    assert!(
+        messages(&diagnostics)
+            .contains(&"Face up of model minecraft:block/tilted has invalid rotation 45".into())
+    );
+}
+
+#[test]
+fn glazed_terracotta_pattern_turns_with_the_block() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:white_glazed_terracotta#facing=west"),
+        [
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta#r=270",
+            "minecraft:block/white_glazed_terracotta#r=90",
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta",
+        ]
+    );
+    assert_eq!(
+        faces(&set, "minecraft:white_glazed_terracotta#facing=east"),
+        [
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta#r=90",
+            "minecraft:block/white_glazed_terracotta#r=270",
+            "minecraft:block/white_glazed_terracotta",
+            "minecraft:block/white_glazed_terracotta",
+        ]
+    );
+}
+
+#[test]
+fn uvlock_keeps_textures_aligned_to_the_world() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:locked_log#axis=z"),
+        [
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log_top",
+        ]
+    );
+    assert_eq!(
+        faces(&set, "minecraft:locked_log#axis=x"),
+        [
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log_top",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+            "minecraft:block/oak_log",
+        ]
+    );
+}
+
+#[test]
+fn uvlock_turns_the_uv_region_with_the_face() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:half_top#locked=false")[2],
+        "minecraft:block/half_top#r=270#uv=0,0,16,8"
+    );
+    assert_eq!(
+        faces(&set, "minecraft:half_top#locked=true")[2],
+        "minecraft:block/half_top#uv=8,0,16,16"
+    );
+}
+
+#[test]
+fn blockstate_rotations_are_taken_modulo_360() {
+    let (set, _) = full_blocks();
+
+    assert_eq!(
+        faces(&set, "minecraft:modded_log#axis=x"),
+        faces(&set, "minecraft:oak_log#axis=x")
+    );
+    assert_eq!(
+        faces(&set, "minecraft:modded_log#axis=z"),
+        faces(&set, "minecraft:oak_log#axis=z")
+    );
+}
+
+#[test]
+fn non_right_blockstate_rotation_is_reported() {
+    let (set, diagnostics) = full_blocks();
+
+    assert!(!set.materials.contains_key("minecraft:modded_log#axis=y"));
+    assert!(
+        messages(&diagnostics).contains(
+            &"Model minecraft:block/oak_log is rotated by 45 degrees in minecraft:modded_log, \
+          which is not a multiple of 90"
+                .into()
+        )
+    );
+}
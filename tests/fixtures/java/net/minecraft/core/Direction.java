@@ -0,0 +1,37 @@
+package net.minecraft.core;
+
+public enum Direction implements StringRepresentable {
+    DOWN(0, 1, -1, "down", Direction.AxisDirection.NEGATIVE, Direction.Axis.Y, new Vec3i(0, -1, 0)),
+    UP(1, 0, -1, "up", Direction.AxisDirection.POSITIVE, Direction.Axis.Y, new Vec3i(0, 1, 0)),
+    NORTH(2, 3, 2, "north", Direction.AxisDirection.NEGATIVE, Direction.Axis.Z, new Vec3i(0, 0, -1)),
+    SOUTH(3, 2, 0, "south", Direction.AxisDirection.POSITIVE, Direction.Axis.Z, new Vec3i(0, 0, 1)),
+    WEST(4, 5, 1, "west", Direction.AxisDirection.NEGATIVE, Direction.Axis.X, new Vec3i(-1, 0, 0)),
+    EAST(5, 4, 3, "east", Direction.AxisDirection.POSITIVE, Direction.Axis.X, new Vec3i(1, 0, 0));
+
+    private final String name;
+
+    public static enum Axis implements StringRepresentable {
+        X("x"),
+        Y("y"),
+        Z("z");
+
+        private final String name;
+
+        private Axis(String name) {
+            this.name = name;
+        }
+    }
+
+    public static enum Plane implements Iterable<Direction> {
+        HORIZONTAL(new Direction[]{Direction.NORTH, Direction.EAST, Direction.SOUTH, Direction.WEST}, new Direction.Axis[]{Direction.Axis.X, Direction.Axis.Z}),
+        VERTICAL(new Direction[]{Direction.UP, Direction.DOWN}, new Direction.Axis[]{Direction.Axis.Y});
+
+        private final Direction[] faces;
+        private final Direction.Axis[] axis;
+
+        private Plane(Direction[] faces, Direction.Axis[] axis) {
+            this.faces = faces;
+            this.axis = axis;
+        }
+    }
+}
@@ -0,0 +1,7 @@
+package net.minecraft.world.level.block;
+
+public abstract class BaseEntityBlock extends Block implements EntityBlock {
+    protected BaseEntityBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+    }
+}
@@ -0,0 +1,28 @@
+package net.minecraft.world.level.block;
+
+public class Blocks {
+    public static final Block STONE = register("stone", Block::new, BlockBehaviour.Properties.of());
+    public static final Block GLASS = register("glass", new Block(BlockBehaviour.Properties.of()));
+    public static final Block OAK_LOG = log("oak_log", MapColor.WOOD, MapColor.PODZOL);
+    public static final Block FURNACE = register("furnace", FurnaceBlock::new, BlockBehaviour.Properties.of());
+    public static final Block HOPPER = register("hopper", HopperBlock::new, BlockBehaviour.Properties.of());
+    public static final Block REDSTONE_WIRE = register("redstone_wire", RedStoneWireBlock::new, BlockBehaviour.Properties.of());
+    public static final Block OAK_SLAB = register("oak_slab", SlabBlock::new, BlockBehaviour.Properties.of());
+    public static final Block LIT_SLAB = register("lit_slab", LitSlabBlock::new, BlockBehaviour.Properties.of());
+    public static final Block OAK_SIGN = register("oak_sign", StandingSignBlock::new, BlockBehaviour.Properties.of());
+    public static final Block COMPOSTER = register("composter", ComposterBlock::new, BlockBehaviour.Properties.of());
+    public static final Block BROKEN = register("broken", BrokenBlock::new, BlockBehaviour.Properties.of());
+    public static final Block MODDED = register("modded", ModdedBlock::new, BlockBehaviour.Properties.of());
+
+    private static Block log(String id, MapColor top, MapColor side) {
+        return register(id, RotatedPillarBlock::new, BlockBehaviour.Properties.of().mapColor(top));
+    }
+
+    private static Block register(String id, Function<BlockBehaviour.Properties, Block> factory, BlockBehaviour.Properties properties) {
+        return Registry.register(BuiltInRegistries.BLOCK, id, factory.apply(properties));
+    }
+
+    private static Block register(String id, Block block) {
+        return Registry.register(BuiltInRegistries.BLOCK, id, block);
+    }
+}
@@ -0,0 +1,13 @@
+package net.minecraft.world.level.block;
+
+public class BrokenBlock extends Block {
+    public BrokenBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.defaultBlockState().setValue(MISSING, true));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        builder.add(MISSING, BlockStateProperties.LIT);
+    }
+}
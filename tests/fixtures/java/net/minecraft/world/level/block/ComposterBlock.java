@@ -0,0 +1,18 @@
+package net.minecraft.world.level.block;
+
+public class ComposterBlock extends Block {
+    public static final int READY = 8;
+    public static final int MIN_LEVEL = 0;
+    public static final int MAX_LEVEL = 7;
+    public static final IntegerProperty LEVEL = BlockStateProperties.LEVEL_COMPOSTER;
+
+    public ComposterBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.stateDefinition.any().setValue(LEVEL, 0));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        builder.add(LEVEL);
+    }
+}
@@ -0,0 +1,7 @@
+package net.minecraft.world.level.block;
+
+public class FurnaceBlock extends AbstractFurnaceBlock {
+    public FurnaceBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+    }
+}
@@ -0,0 +1,16 @@
+package net.minecraft.world.level.block;
+
+public class HopperBlock extends BaseEntityBlock {
+    public static final EnumProperty<Direction> FACING = BlockStateProperties.FACING_HOPPER;
+    public static final BooleanProperty ENABLED = BlockStateProperties.ENABLED;
+
+    public HopperBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.stateDefinition.any().setValue(FACING, Direction.DOWN).setValue(ENABLED, Boolean.valueOf(true)));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        builder.add(new Property[]{FACING, ENABLED});
+    }
+}
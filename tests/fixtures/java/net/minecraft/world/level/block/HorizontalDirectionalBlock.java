@@ -0,0 +1,9 @@
+package net.minecraft.world.level.block;
+
+public abstract class HorizontalDirectionalBlock extends Block {
+    public static final EnumProperty<Direction> FACING = BlockStateProperties.HORIZONTAL_FACING;
+
+    protected HorizontalDirectionalBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+    }
+}
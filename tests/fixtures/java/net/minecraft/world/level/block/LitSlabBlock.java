@@ -0,0 +1,16 @@
+package net.minecraft.world.level.block;
+
+public class LitSlabBlock extends SlabBlock {
+    public static final BooleanProperty LIT = BlockStateProperties.LIT;
+
+    public LitSlabBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.defaultBlockState().setValue(LIT, true));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        super.createBlockStateDefinition(builder);
+        builder.add(LIT);
+    }
+}
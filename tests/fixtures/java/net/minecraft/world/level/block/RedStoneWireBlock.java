@@ -0,0 +1,15 @@
+package net.minecraft.world.level.block;
+
+public class RedStoneWireBlock extends Block {
+    public static final IntegerProperty POWER = BlockStateProperties.POWER;
+
+    public RedStoneWireBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.stateDefinition.any().setValue(POWER, 0));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        builder.add(POWER);
+    }
+}
@@ -0,0 +1,15 @@
+package net.minecraft.world.level.block;
+
+public class StandingSignBlock extends Block {
+    public static final IntegerProperty ROTATION = BlockStateProperties.ROTATION_16;
+
+    public StandingSignBlock(BlockBehaviour.Properties properties) {
+        super(properties);
+        this.registerDefaultState(this.stateDefinition.any().setValue(ROTATION, 0));
+    }
+
+    @Override
+    protected void createBlockStateDefinition(StateDefinition.Builder<Block, BlockState> builder) {
+        builder.add(ROTATION);
+    }
+}
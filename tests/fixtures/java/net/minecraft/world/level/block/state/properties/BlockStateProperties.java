@@ -0,0 +1,16 @@
+package net.minecraft.world.level.block.state.properties;
+
+public class BlockStateProperties {
+    public static final BooleanProperty ENABLED = BooleanProperty.create("enabled");
+    public static final BooleanProperty LIT = BooleanProperty.create("lit");
+    public static final BooleanProperty WATERLOGGED = BooleanProperty.create("waterlogged");
+    public static final EnumProperty<Direction.Axis> AXIS = EnumProperty.create("axis", Direction.Axis.class);
+    public static final EnumProperty<Direction> HORIZONTAL_FACING = EnumProperty.create("facing", Direction.class, Direction.Plane.HORIZONTAL);
+    public static final EnumProperty<Direction> FACING_HOPPER = EnumProperty.create("facing", Direction.class, facing -> facing != Direction.UP);
+    public static final int MAX_ROTATIONS_16 = RotationSegment.getMaxSegmentIndex();
+    public static final int MAX_LEVEL_8 = 8;
+    public static final IntegerProperty POWER = IntegerProperty.create("power", 0, 15);
+    public static final IntegerProperty ROTATION_16 = IntegerProperty.create("rotation", 0, MAX_ROTATIONS_16);
+    public static final IntegerProperty LEVEL_COMPOSTER = IntegerProperty.create("level", 0, MAX_LEVEL_8);
+    public static final EnumProperty<SlabType> SLAB_TYPE = EnumProperty.create("type", SlabType.class);
+}
@@ -0,0 +1,9 @@
+package net.minecraft.world.level.block.state.properties;
+
+public class RotationSegment {
+    private static final int MAX_SEGMENT_INDEX = 15;
+
+    public static int getMaxSegmentIndex() {
+        return MAX_SEGMENT_INDEX;
+    }
+}
@@ -0,0 +1,13 @@
+package net.minecraft.world.level.block.state.properties;
+
+public enum SlabType implements StringRepresentable {
+    TOP("top"),
+    BOTTOM("bottom"),
+    DOUBLE("double");
+
+    private final String name;
+
+    private SlabType(String name) {
+        this.name = name;
+    }
+}
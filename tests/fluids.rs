@@ -0,0 +1,102 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{MemorySource, image};
+use minecraft_blocks::{
+    AssetStore, Diagnostics, Error, palette::MaterialDisplay, source::LayeredSource, tint::WATER,
+};
+
+fn fluids() -> MemorySource {
+    MemorySource::default()
+        .with_file(
+            "assets/minecraft/blockstates/water.json",
+            r#"{"variants": {"": {"model": "minecraft:block/water"}}}"#,
+        )
+        .with_file(
+            "assets/minecraft/blockstates/lava.json",
+            r#"{"variants": {
+                "level=0": {"model": "minecraft:block/lava"},
+                "level=1": {"model": "minecraft:block/lava"}
+            }}"#,
+        )
+        .with_file(
+            "assets/minecraft/models/block/water.json",
+            r#"{"textures": {"particle": "block/water_still"}}"#,
+        )
+        .with_file(
+            "assets/minecraft/models/block/lava.json",
+            r#"{"textures": {"particle": "block/lava_still"}}"#,
+        )
+        .with_texture(
+            "block/water_still",
+            &image(16, 32, |_, y| [0, 0, if y < 16 { 200 } else { 250 }, 180]),
+        )
+        .with_file(
+            "assets/minecraft/textures/block/water_still.png.mcmeta",
+            r#"{"animation": {"frametime": 2}}"#,
+        )
+        .with_texture(
+            "block/lava_still",
+            &image(16, 16, |_, _| [250, 100, 0, 255]),
+        )
+}
+
+fn load(source: MemorySource, diagnostics: &mut Diagnostics) -> AssetStore {
+    let mut layers = LayeredSource::default();
+    layers.push("fluids".to_owned(), Box::new(source));
+    AssetStore::load(layers, diagnostics).unwrap()
+}
+
+#[test]
+fn fluids_show_their_still_texture() {
+    let mut diagnostics = Diagnostics::default();
+    let assets = load(fluids(), &mut diagnostics);
+    let set = assets.full_cube_materials(false, &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    let MaterialDisplay::TextureAnimation { frames, delay } =
+        &set.materials["minecraft:water"].display
+    else {
+        panic!("water is not animated");
+    };
+    assert_eq!(*delay, Duration::from_millis(100));
+    assert_eq!(frames.len(), 2);
+    for (i, frame) in frames.iter().enumerate() {
+        for face in frame.faces() {
+            assert_eq!(
+                face.path.to_string(),
+                format!("minecraft:block/water_still/{}", i)
+            );
+            assert_eq!(face.tint, Some(WATER));
+        }
+    }
+    assert_eq!(set.frames.len(), 2);
+
+    let MaterialDisplay::Texture(lava) = &set.materials["minecraft:lava#level=0"].display else {
+        panic!("lava is not a static texture");
+    };
+    for face in lava.faces() {
+        assert_eq!(face.path.to_string(), "minecraft:block/lava_still");
+        assert_eq!(face.tint, None);
+    }
+
+    // Flowing fluids are left out
+    assert!(!set.materials.contains_key("minecraft:lava#level=1"));
+    assert_eq!(set.materials.len(), 2);
+}
+
+#[test]
+fn fluid_without_a_particle_texture_is_reported() {
+    let source = fluids().with_file("assets/minecraft/models/block/lava.json", "{}");
+    let mut diagnostics = Diagnostics::default();
+    let assets = load(source, &mut diagnostics);
+    let set = assets.full_cube_materials(false, &mut diagnostics);
+
+    assert!(!set.materials.contains_key("minecraft:lava#level=0"));
+    assert!(matches!(
+        diagnostics.errors(),
+        [Error::UnresolvedTexture { model, variable }]
+            if model.to_string() == "minecraft:block/lava" && variable == "particle"
+    ));
+}
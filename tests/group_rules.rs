@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use minecraft_blocks::palette::GroupRule;
+use serde_json::json;
+
+#[test]
+fn unit_random_choice_reads_as_equal_weights() {
+    let rule: GroupRule = serde_json::from_str(r#""random_choice""#).unwrap();
+
+    assert_eq!(
+        rule,
+        GroupRule::RandomChoice {
+            weights: BTreeMap::new()
+        }
+    );
+}
+
+#[test]
+fn weighted_random_choice_is_read() {
+    let rule: GroupRule =
+        serde_json::from_str(r#"{"random_choice": {"weights": {"minecraft:stone@0": 3}}}"#)
+            .unwrap();
+
+    assert_eq!(
+        rule,
+        GroupRule::RandomChoice {
+            weights: BTreeMap::from([("minecraft:stone@0".to_owned(), 3)])
+        }
+    );
+}
+
+#[test]
+fn random_choice_without_weights_is_read() {
+    let rule: GroupRule = serde_json::from_str(r#"{"random_choice": {}}"#).unwrap();
+
+    assert_eq!(
+        rule,
+        GroupRule::RandomChoice {
+            weights: BTreeMap::new()
+        }
+    );
+}
+
+#[test]
+fn custom_rule_is_read() {
+    let rule: GroupRule = serde_json::from_str(r#"{"custom": {"pattern": "checker"}}"#).unwrap();
+
+    assert_eq!(rule, GroupRule::Custom(json!({"pattern": "checker"})));
+}
+
+#[test]
+fn rules_round_trip() {
+    for rule in [
+        GroupRule::RandomChoice {
+            weights: BTreeMap::new(),
+        },
+        GroupRule::RandomChoice {
+            weights: BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]),
+        },
+        GroupRule::Custom(json!([1, 2])),
+    ] {
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(serde_json::from_str::<GroupRule>(&json).unwrap(), rule);
+    }
+}
+
+#[test]
+fn equal_weights_are_written_as_a_unit() {
+    let rule = GroupRule::RandomChoice {
+        weights: BTreeMap::new(),
+    };
+    assert_eq!(serde_json::to_value(&rule).unwrap(), json!("random_choice"));
+
+    let rule = GroupRule::RandomChoice {
+        weights: BTreeMap::from([("a".to_owned(), 2)]),
+    };
+    assert_eq!(
+        serde_json::to_value(&rule).unwrap(),
+        json!({"random_choice": {"weights": {"a": 2}}})
+    );
+}
+
+#[test]
+fn unknown_rule_is_rejected() {
+    assert!(serde_json::from_str::<GroupRule>(r#""custom""#).is_err());
+    assert!(serde_json::from_str::<GroupRule>(r#"{"majority": {}}"#).is_err());
+}
@@ -0,0 +1,176 @@
+use minecraft_blocks::{
+    Diagnostics, Error,
+    java::load_block_registry,
+    registry::{BlockDefinition, BlockRegistry},
+    schema::location::ResourceLocation,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/java");
+
+fn registry() -> (BlockRegistry, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let registry = load_block_registry(FIXTURES, &mut diagnostics).unwrap();
+    (registry, diagnostics)
+}
+
+fn block<'r>(registry: &'r BlockRegistry, id: &str) -> &'r BlockDefinition {
+    registry
+        .blocks
+        .get(&id.parse::<ResourceLocation>().unwrap())
+        .unwrap_or_else(|| panic!("{} is not registered", id))
+}
+
+fn values(definition: &BlockDefinition, property: &str) -> Vec<String> {
+    definition.properties[property].clone()
+}
+
+#[test]
+fn blocks_are_registered_in_order() {
+    let (registry, _) = registry();
+
+    let ids: Vec<String> = registry.blocks.keys().map(|id| id.to_string()).collect();
+    assert_eq!(
+        ids,
+        [
+            "minecraft:stone",
+            "minecraft:glass",
+            "minecraft:oak_log",
+            "minecraft:furnace",
+            "minecraft:hopper",
+            "minecraft:redstone_wire",
+            "minecraft:oak_slab",
+            "minecraft:lit_slab",
+            "minecraft:oak_sign",
+            "minecraft:composter",
+        ]
+    );
+}
+
+#[test]
+fn block_without_properties_has_none() {
+    let (registry, _) = registry();
+
+    let stone = block(&registry, "minecraft:stone");
+    assert!(stone.properties.is_empty());
+    assert_eq!(stone.default_state_key(), "");
+}
+
+#[test]
+fn enum_property_lists_every_constant() {
+    let (registry, _) = registry();
+
+    let log = block(&registry, "minecraft:oak_log");
+    assert_eq!(values(log, "axis"), ["x", "y", "z"]);
+    assert_eq!(log.default_state_key(), "axis=y");
+}
+
+#[test]
+fn plane_domain_keeps_the_plane_order() {
+    let (registry, _) = registry();
+
+    let furnace = block(&registry, "minecraft:furnace");
+    assert_eq!(
+        values(furnace, "facing"),
+        ["north", "east", "south", "west"]
+    );
+}
+
+#[test]
+fn boolean_property_is_true_then_false() {
+    let (registry, _) = registry();
+
+    let furnace = block(&registry, "minecraft:furnace");
+    assert_eq!(values(furnace, "lit"), ["true", "false"]);
+}
+
+#[test]
+fn properties_and_defaults_are_inherited() {
+    let (registry, _) = registry();
+
+    // FurnaceBlock declares nothing itself, AbstractFurnaceBlock does
+    let furnace = block(&registry, "minecraft:furnace");
+    assert_eq!(furnace.default_state_key(), "facing=north,lit=false");
+}
+
+#[test]
+fn lambda_filter_excludes_a_constant() {
+    let (registry, _) = registry();
+
+    let hopper = block(&registry, "minecraft:hopper");
+    assert_eq!(
+        values(hopper, "facing"),
+        ["down", "north", "south", "west", "east"]
+    );
+    assert_eq!(hopper.default_state_key(), "enabled=true,facing=down");
+}
+
+#[test]
+fn integer_property_covers_its_range() {
+    let (registry, _) = registry();
+
+    let wire = block(&registry, "minecraft:redstone_wire");
+    let expected: Vec<String> = (0..=15).map(|i| i.to_string()).collect();
+    assert_eq!(values(wire, "power"), expected);
+    assert_eq!(wire.default_state_key(), "power=0");
+}
+
+#[test]
+fn integer_bounds_resolve_int_constants() {
+    let (registry, _) = registry();
+
+    // MAX_ROTATIONS_16 is RotationSegment.getMaxSegmentIndex()
+    let sign = block(&registry, "minecraft:oak_sign");
+    let expected: Vec<String> = (0..=15).map(|i| i.to_string()).collect();
+    assert_eq!(values(sign, "rotation"), expected);
+    assert_eq!(sign.default_state_key(), "rotation=0");
+
+    let composter = block(&registry, "minecraft:composter");
+    let expected: Vec<String> = (0..=8).map(|i| i.to_string()).collect();
+    assert_eq!(values(composter, "level"), expected);
+}
+
+#[test]
+fn enum_values_are_serialized_names() {
+    let (registry, _) = registry();
+
+    let slab = block(&registry, "minecraft:oak_slab");
+    assert_eq!(values(slab, "type"), ["top", "bottom", "double"]);
+    assert_eq!(slab.default_state_key(), "type=bottom,waterlogged=false");
+}
+
+#[test]
+fn super_call_keeps_inherited_properties_and_defaults() {
+    let (registry, _) = registry();
+
+    let slab = block(&registry, "minecraft:lit_slab");
+    let mut properties: Vec<&str> = slab.properties.keys().map(String::as_str).collect();
+    properties.sort();
+    assert_eq!(properties, ["lit", "type", "waterlogged"]);
+    assert_eq!(
+        slab.default_state_key(),
+        "lit=true,type=bottom,waterlogged=false"
+    );
+}
+
+#[test]
+fn unresolved_property_leaves_the_block_out() {
+    let (registry, diagnostics) = registry();
+
+    // Its states are inferred from its blockstate instead
+    assert!(
+        !registry
+            .blocks
+            .contains_key(&"minecraft:broken".parse::<ResourceLocation>().unwrap())
+    );
+
+    let unresolved: Vec<(&str, &str)> = diagnostics
+        .errors()
+        .iter()
+        .map(|e| match e {
+            Error::UnresolvedProperty { class, property } => (class.as_str(), property.as_str()),
+            other => panic!("unexpected diagnostic: {}", other),
+        })
+        .collect();
+    // Both createBlockStateDefinition and registerDefaultState use it, reported once
+    assert_eq!(unresolved, [("BrokenBlock", "MISSING")]);
+}
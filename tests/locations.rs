@@ -0,0 +1,76 @@
+use minecraft_blocks::schema::{location::ResourceLocation, model::TextureRef};
+
+fn location(s: &str) -> ResourceLocation {
+    s.parse().unwrap()
+}
+
+#[test]
+fn missing_namespace_is_minecraft() {
+    assert_eq!(
+        location("block/stone"),
+        ResourceLocation::minecraft("block/stone")
+    );
+    assert_eq!(
+        location("example:block/ruby_ore"),
+        ResourceLocation::new("example", "block/ruby_ore")
+    );
+    assert_eq!(location("block/stone").to_string(), "minecraft:block/stone");
+}
+
+#[test]
+fn invalid_characters_are_rejected() {
+    for invalid in [
+        "",
+        ":block/stone",
+        "minecraft:",
+        "Minecraft:block/stone",
+        "minecraft:block/Stone",
+        "minecraft:block stone",
+        "minecraft:block/stone#0",
+        "mine/craft:block/stone",
+        "a:b:c",
+    ] {
+        assert!(
+            invalid.parse::<ResourceLocation>().is_err(),
+            "{:?} was accepted",
+            invalid
+        );
+    }
+
+    // Dots, dashes and underscores are allowed in both parts
+    assert_eq!(
+        location("my-mod.v2:block/a_b.c-d"),
+        ResourceLocation::new("my-mod.v2", "block/a_b.c-d")
+    );
+}
+
+#[test]
+fn texture_refs_are_variables_or_locations() {
+    let texture = |s: &str| -> TextureRef { serde_json::from_value(s.into()).unwrap() };
+
+    assert_eq!(texture("#all"), TextureRef::Variable("all".to_owned()));
+    assert_eq!(
+        texture("block/stone"),
+        TextureRef::Location(ResourceLocation::minecraft("block/stone"))
+    );
+    assert_eq!(
+        serde_json::to_value(TextureRef::Variable("side".to_owned())).unwrap(),
+        "#side"
+    );
+    assert!(serde_json::from_value::<TextureRef>("block/Stone".into()).is_err());
+}
+
+#[test]
+fn locations_name_their_files() {
+    let ore = location("example:block/ruby_ore");
+
+    assert_eq!(
+        ore.asset_path("textures", "png"),
+        "assets/example/textures/block/ruby_ore.png"
+    );
+    assert_eq!(
+        location("stone").asset_path("blockstates", "json"),
+        "assets/minecraft/blockstates/stone.json"
+    );
+    assert_eq!(ore.file_stem(), "example/block/ruby_ore");
+}
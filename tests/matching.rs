@@ -0,0 +1,432 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{cube, material, palette, volume};
+use indexmap::IndexMap;
+use minecraft_blocks::{
+    matching::{DeltaE, MatchFace, MatchFilter, cie94, ciede2000, cielab, oklab},
+    palette::{BlockIds, Color, Group, GroupRule, Palette},
+    shapes::Shape,
+};
+use serde_json::json;
+
+fn assert_close<const N: usize>(actual: [f64; N], expected: [f64; N], tolerance: f64) {
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(
+            (a - e).abs() <= tolerance,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn cielab_of_srgb_primaries() {
+    assert_close(cielab(Color::rgb(0xffffff)), [100.0, 0.0, 0.0], 1e-3);
+    assert_close(cielab(Color::rgb(0x000000)), [0.0, 0.0, 0.0], 1e-3);
+    assert_close(cielab(Color::rgb(0x808080)), [53.585, 0.0, 0.0], 1e-3);
+    assert_close(
+        cielab(Color::rgb(0xff0000)),
+        [53.2408, 80.0925, 67.2032],
+        1e-3,
+    );
+    assert_close(
+        cielab(Color::rgb(0x00ff00)),
+        [87.7347, -86.1827, 83.1793],
+        1e-3,
+    );
+    assert_close(
+        cielab(Color::rgb(0x0000ff)),
+        [32.2970, 79.1875, -107.8602],
+        1e-3,
+    );
+}
+
+#[test]
+fn oklab_of_srgb_primaries() {
+    assert_close(oklab(Color::rgb(0xffffff)), [1.0, 0.0, 0.0], 1e-4);
+    assert_close(oklab(Color::rgb(0x000000)), [0.0, 0.0, 0.0], 1e-4);
+    assert_close(
+        oklab(Color::rgb(0xff0000)),
+        [0.627955, 0.224863, 0.125846],
+        1e-4,
+    );
+    assert_close(
+        oklab(Color::rgb(0x00ff00)),
+        [0.866440, -0.233888, 0.179498],
+        1e-4,
+    );
+    assert_close(
+        oklab(Color::rgb(0x0000ff)),
+        [0.452014, -0.032457, -0.311528],
+        1e-4,
+    );
+}
+
+/// Test data of Sharma, Wu and Dalal, "The CIEDE2000 color-difference formula:
+/// implementation notes, supplementary test data, and mathematical observations" (2005).
+const SHARMA: [([f64; 3], [f64; 3], f64); 34] = [
+    ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+    ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+    ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+    ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+    ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+    ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+    ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+    ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+    ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+    ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+    ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+    (
+        [60.2574, -34.0099, 36.2677],
+        [60.4626, -34.1751, 39.4387],
+        1.2644,
+    ),
+    (
+        [63.0109, -31.0961, -5.8663],
+        [62.8187, -29.7946, -4.0864],
+        1.2630,
+    ),
+    (
+        [61.2901, 3.7196, -5.3901],
+        [61.4292, 2.2480, -4.9620],
+        1.8731,
+    ),
+    (
+        [35.0831, -44.1164, 3.7933],
+        [35.0232, -40.0716, 1.5901],
+        1.8645,
+    ),
+    (
+        [22.7233, 20.0904, -46.6940],
+        [23.0331, 14.9730, -42.5619],
+        2.0373,
+    ),
+    (
+        [36.4612, 47.8580, 18.3852],
+        [36.2715, 50.5065, 21.2231],
+        1.4146,
+    ),
+    (
+        [90.8027, -2.0831, 1.4410],
+        [91.1528, -1.6435, 0.0447],
+        1.4441,
+    ),
+    (
+        [90.9257, -0.5406, -0.9208],
+        [88.6381, -0.8985, -0.7239],
+        1.5381,
+    ),
+    (
+        [6.7747, -0.2908, -2.4247],
+        [5.8714, -0.0985, -2.2286],
+        0.6377,
+    ),
+    (
+        [2.0776, 0.0795, -1.1350],
+        [0.9033, -0.0636, -0.5514],
+        0.9082,
+    ),
+];
+
+#[test]
+fn ciede2000_matches_sharma_test_data() {
+    for (lab1, lab2, expected) in SHARMA {
+        assert_close([ciede2000(lab1, lab2)], [expected], 1e-4);
+        assert_close([ciede2000(lab2, lab1)], [expected], 1e-4);
+    }
+}
+
+#[test]
+fn cie94_weights_by_the_reference_chroma() {
+    assert_close(
+        [cie94([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485])],
+        [1.3950],
+        1e-4,
+    );
+    // Neutral references weigh nothing down, so this is the CIE76 difference
+    assert_close(
+        [cie94([50.0, 0.0, 0.0], [50.0, -1.0, 2.0])],
+        [5.0_f64.sqrt()],
+        1e-9,
+    );
+    assert_close(
+        [cie94([50.0, -1.0, 2.0], [50.0, 0.0, 0.0])],
+        [5.0_f64.sqrt() / (1.0 + 0.045 * 5.0_f64.sqrt())],
+        1e-9,
+    );
+    assert_close([cie94([40.0, 10.0, 10.0], [43.0, 10.0, 10.0])], [3.0], 1e-9);
+}
+
+/// Every gray from black to white, 1 apart.
+fn grays() -> Palette {
+    palette(
+        (0..=255u32)
+            .map(|v| {
+                let id: &'static str = format!("gray_{:03}", v).leak();
+                (id, cube(id, Color::rgb(v * 0x010101)))
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn nearest_n_orders_by_distance() {
+    let palette = grays();
+
+    for delta_e in [DeltaE::Ok, DeltaE::Cie76, DeltaE::Cie94, DeltaE::Ciede2000] {
+        let index = palette.color_index(delta_e, &MatchFilter::default());
+        assert_eq!(index.len(), 256);
+
+        let matches = index.nearest_n(Color::rgb(0x646464), 5);
+        let ids: Vec<&str> = matches.iter().map(|m| m.id).collect();
+        assert_eq!(ids[0], "gray_100", "{:?}", delta_e);
+        assert_eq!(
+            ids[1..].iter().collect::<BTreeSet<_>>(),
+            ["gray_098", "gray_099", "gray_101", "gray_102"]
+                .iter()
+                .collect::<BTreeSet<_>>(),
+            "{:?}",
+            delta_e
+        );
+        assert_eq!(matches[0].distance, 0.0);
+        assert!(matches.windows(2).all(|m| m[0].distance <= m[1].distance));
+
+        assert!(index.nearest_n(Color::rgb(0x646464), 0).is_empty());
+        assert_eq!(index.nearest_n(Color::rgb(0x646464), 300).len(), 256);
+    }
+}
+
+#[test]
+fn equally_close_materials_are_ordered_by_id() {
+    let palette = palette(vec![
+        ("b", cube("b", Color::rgb(0x808080))),
+        ("a", cube("a", Color::rgb(0x808080))),
+        ("c", cube("c", Color::rgb(0x808080))),
+    ]);
+    let index = palette.color_index(DeltaE::Ok, &MatchFilter::default());
+
+    let ids: Vec<&str> = index
+        .nearest_n(Color::rgb(0x000000), 3)
+        .iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+}
+
+#[test]
+fn nearest_is_the_nearest_by_the_formula() {
+    // Saturated colors are far apart in CIELAB but close by CIE94 and CIEDE2000, so the
+    // nearest material can be far behind in Euclidean order
+    let mut materials = Vec::new();
+    for r in (0..=255u32).step_by(51) {
+        for g in (0..=255u32).step_by(51) {
+            for b in (0..=255u32).step_by(51) {
+                let id: &'static str = format!("{:02x}{:02x}{:02x}", r, g, b).leak();
+                materials.push((id, cube(id, Color::rgb(r << 16 | g << 8 | b))));
+            }
+        }
+    }
+    let palette = palette(materials);
+
+    for delta_e in [DeltaE::Cie94, DeltaE::Ciede2000] {
+        let index = palette.color_index(delta_e, &MatchFilter::default());
+        for query in (0..=0xffffffu32).step_by(0x0f0f0f + 0x030507) {
+            let color = Color::rgb(query);
+            let all = index.nearest_n(color, index.len());
+            assert_eq!(index.nearest(color), all.first().copied(), "{:?}", delta_e);
+            assert_eq!(index.nearest_n(color, 3), all[..3], "{:?}", delta_e);
+        }
+    }
+}
+
+#[test]
+fn alpha_matches_translucent_materials() {
+    let palette = palette(vec![
+        ("opaque", cube("opaque", Color::rgb(0x3050a0))),
+        (
+            "glass",
+            cube(
+                "glass",
+                Color {
+                    r: 0x30,
+                    g: 0x50,
+                    b: 0xa0,
+                    a: 0x40,
+                },
+            ),
+        ),
+    ]);
+    let query = Color {
+        r: 0x30,
+        g: 0x50,
+        b: 0xa0,
+        a: 0x50,
+    };
+
+    for delta_e in [DeltaE::Ok, DeltaE::Cie76, DeltaE::Cie94, DeltaE::Ciede2000] {
+        let index = palette.color_index(delta_e, &MatchFilter::default());
+        assert_eq!(index.nearest(query).unwrap().id, "glass", "{:?}", delta_e);
+    }
+}
+
+#[test]
+fn groups_filter_materials() {
+    let mut palette = palette(vec![
+        ("red", cube("red", Color::rgb(0xff0000))),
+        ("dark_red", cube("dark_red", Color::rgb(0x800000))),
+        ("blue", cube("blue", Color::rgb(0x0000ff))),
+    ]);
+    palette.groups.insert(
+        "blues".to_owned(),
+        Group {
+            block_ids: BlockIds::Blocks(BTreeSet::from(["blue".to_owned()])),
+            rule: GroupRule::Custom(json!(null)),
+        },
+    );
+    palette.groups.insert(
+        "darks".to_owned(),
+        Group {
+            block_ids: BlockIds::Blocks(BTreeSet::from(["dark_red".to_owned()])),
+            rule: GroupRule::Custom(json!(null)),
+        },
+    );
+
+    let filter = MatchFilter {
+        groups: vec!["blues".to_owned()],
+        ..Default::default()
+    };
+    let index = palette.color_index(DeltaE::Ok, &filter);
+    assert_eq!(index.len(), 1);
+    assert_eq!(index.nearest(Color::rgb(0xff0000)).unwrap().id, "blue");
+
+    let filter = MatchFilter {
+        groups: vec!["blues".to_owned(), "darks".to_owned()],
+        ..Default::default()
+    };
+    let index = palette.color_index(DeltaE::Ok, &filter);
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.nearest(Color::rgb(0xff0000)).unwrap().id, "dark_red");
+
+    let filter = MatchFilter {
+        groups: vec!["missing".to_owned()],
+        ..Default::default()
+    };
+    let index = palette.color_index(DeltaE::Ok, &filter);
+    assert!(index.is_empty());
+    assert_eq!(index.nearest(Color::rgb(0xff0000)), None);
+}
+
+#[test]
+fn shapes_filter_materials() {
+    let palette = palette(vec![
+        ("stone", cube("stone", Color::rgb(0x7f7f7f))),
+        (
+            "minecraft:stone_slab#type=bottom",
+            volume("minecraft/stone_slab_bottom", Color::rgb(0x808080)),
+        ),
+        (
+            "minecraft:stone_slab#type=top@1",
+            volume("minecraft/stone_slab_top", Color::rgb(0x818181)),
+        ),
+        (
+            "minecraft:unknown",
+            volume("minecraft/unknown", Color::rgb(0x808080)),
+        ),
+    ]);
+    let block_shapes = IndexMap::from([
+        (
+            "minecraft:stone_slab#type=bottom".to_owned(),
+            Shape::BottomSlab,
+        ),
+        ("minecraft:stone_slab#type=top".to_owned(), Shape::TopSlab),
+    ]);
+    let ids = |shapes: Vec<Shape>| {
+        let filter = MatchFilter {
+            shapes,
+            block_shapes: Some(&block_shapes),
+            ..Default::default()
+        };
+        let index = palette.color_index(DeltaE::Ok, &filter);
+        index
+            .nearest_n(Color::rgb(0x808080), 10)
+            .into_iter()
+            .map(|m| m.id.to_owned())
+            .collect::<BTreeSet<_>>()
+    };
+
+    assert_eq!(ids(vec![]).len(), 4);
+    assert_eq!(
+        ids(vec![Shape::FullCube]),
+        BTreeSet::from(["stone".to_owned()])
+    );
+    assert_eq!(
+        ids(vec![Shape::BottomSlab, Shape::TopSlab]),
+        BTreeSet::from([
+            "minecraft:stone_slab#type=bottom".to_owned(),
+            "minecraft:stone_slab#type=top@1".to_owned()
+        ])
+    );
+    assert!(ids(vec![Shape::Stair]).is_empty());
+
+    // Without shapes of blocks, volumes have none
+    let filter = MatchFilter {
+        shapes: vec![Shape::BottomSlab],
+        ..Default::default()
+    };
+    assert!(palette.color_index(DeltaE::Ok, &filter).is_empty());
+}
+
+#[test]
+fn face_picks_the_matched_color() {
+    let (red, green, blue, white) = (
+        Color::rgb(0xff0000),
+        Color::rgb(0x00ff00),
+        Color::rgb(0x0000ff),
+        Color::rgb(0xffffff),
+    );
+    let palette = palette(vec![
+        ("a", volume("a", red)),
+        (
+            "b",
+            material(
+                json!({ "path": "b" }),
+                [white, white, green, white, blue, white],
+            ),
+        ),
+    ]);
+
+    for (face, expected) in [
+        (MatchFace::All, red),
+        (MatchFace::East, red),
+        (MatchFace::West, white),
+        (MatchFace::Top, green),
+        (MatchFace::Bottom, white),
+        (MatchFace::North, blue),
+        (MatchFace::South, white),
+    ] {
+        let filter = MatchFilter {
+            face,
+            ..Default::default()
+        };
+        let index = palette.color_index(DeltaE::Ok, &filter);
+        let matched = index.nearest(expected).unwrap();
+        assert_eq!(matched.distance, 0.0, "{:?}", face);
+        assert_eq!(matched.color, expected, "{:?}", face);
+    }
+}
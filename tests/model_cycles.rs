@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use minecraft_blocks::{
+    Diagnostics, Error,
+    baked::{BakedModel, MAX_PARENT_DEPTH},
+    schema::{
+        location::ResourceLocation,
+        model::{Model, TextureRef},
+    },
+};
+
+fn models(json: &[(&str, &str)]) -> HashMap<ResourceLocation, Model> {
+    json.iter()
+        .map(|(name, model)| (name.parse().unwrap(), serde_json::from_str(model).unwrap()))
+        .collect()
+}
+
+fn bake(name: &str, models: &HashMap<ResourceLocation, Model>) -> (BakedModel, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let baked = BakedModel::bake(&name.parse().unwrap(), models, &mut diagnostics).unwrap();
+    (baked, diagnostics)
+}
+
+fn messages(diagnostics: &Diagnostics) -> Vec<String> {
+    diagnostics.errors().iter().map(|e| e.to_string()).collect()
+}
+
+#[test]
+fn self_parent_is_reported() {
+    let models = models(&[(
+        "block/loop",
+        r##"{"parent": "block/loop", "textures": {"all": "block/stone"}}"##,
+    )]);
+
+    let (baked, diagnostics) = bake("block/loop", &models);
+
+    assert_eq!(
+        messages(&diagnostics),
+        ["Model parents form a cycle: minecraft:block/loop -> minecraft:block/loop"]
+    );
+    assert_eq!(
+        baked.textures["all"],
+        TextureRef::Location("block/stone".parse().unwrap())
+    );
+}
+
+#[test]
+fn two_model_cycle_is_reported_once() {
+    let models = models(&[
+        ("block/a", r#"{"parent": "block/b"}"#),
+        ("block/b", r#"{"parent": "block/a"}"#),
+        ("block/c", r#"{"parent": "block/b"}"#),
+    ]);
+    let mut diagnostics = Diagnostics::default();
+
+    for name in ["block/a", "block/b", "block/c"] {
+        BakedModel::bake(&name.parse().unwrap(), &models, &mut diagnostics).unwrap();
+    }
+
+    assert_eq!(
+        messages(&diagnostics),
+        ["Model parents form a cycle: minecraft:block/a -> minecraft:block/b -> minecraft:block/a"]
+    );
+    assert!(matches!(
+        &diagnostics.errors()[0],
+        Error::ParentCycle { chain } if chain.len() == 3
+    ));
+}
+
+#[test]
+fn deep_parent_chain_is_cut_off() {
+    let names: Vec<String> = (0..=MAX_PARENT_DEPTH)
+        .map(|i| format!("block/m{}", i))
+        .collect();
+    let json: Vec<String> = names
+        .iter()
+        .skip(1)
+        .map(|parent| format!(r#"{{"parent": "{}"}}"#, parent))
+        .chain([r#"{}"#.to_owned()])
+        .collect();
+    let models = models(
+        &names
+            .iter()
+            .zip(&json)
+            .map(|(n, j)| (n.as_str(), j.as_str()))
+            .collect::<Vec<_>>(),
+    );
+
+    let (_, diagnostics) = bake("block/m0", &models);
+
+    assert!(matches!(
+        diagnostics.errors(),
+        [Error::ParentChainTooDeep { max_depth, .. }] if *max_depth == MAX_PARENT_DEPTH
+    ));
+}
+
+#[test]
+fn texture_variable_loop_is_reported() {
+    let models = models(&[
+        (
+            "block/parent",
+            r##"{"textures": {"b": "#a"}, "elements": [{"from": [0, 0, 0], "to": [16, 16, 16], "faces": {"up": {"texture": "#a"}}}]}"##,
+        ),
+        (
+            "block/child",
+            r##"{"parent": "block/parent", "textures": {"a": "#b", "c": "#c"}}"##,
+        ),
+    ]);
+
+    let (baked, diagnostics) = bake("block/child", &models);
+    let mut messages = messages(&diagnostics);
+    messages.sort();
+
+    assert_eq!(
+        messages,
+        [
+            "Texture variables of model minecraft:block/child form a cycle: #a -> #b -> #a",
+            "Texture variables of model minecraft:block/child form a cycle: #c -> #c",
+        ]
+    );
+
+    let face = &baked.elements[0].faces["up"];
+    assert!(matches!(
+        baked.face_texture(face),
+        Err(Error::UnresolvedTexture { .. })
+    ));
+}
@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use minecraft_blocks::palette::{BlockTexture, Color, CubeRotation, FaceTexture, Rotation};
+use proptest::prelude::*;
+
+fn rotation() -> impl Strategy<Value = Rotation> {
+    prop::sample::select(Rotation::ALL.to_vec())
+}
+
+fn cube_rotation() -> impl Strategy<Value = CubeRotation> {
+    prop::sample::select(CubeRotation::all())
+}
+
+fn face(name: &'static str) -> impl Strategy<Value = FaceTexture> {
+    let uv = prop::option::of(prop::array::uniform4(0..=16u8).prop_map(|uv| uv.map(f32::from)));
+    let tint = prop::option::of(any::<u32>().prop_map(Color::rgb));
+
+    (rotation(), any::<bool>(), any::<bool>(), uv, tint).prop_map(
+        move |(r, flip_x, flip_y, uv, tint)| FaceTexture {
+            rotation: r,
+            flip_x,
+            flip_y,
+            uv,
+            tint,
+            ..FaceTexture::new(format!("test:block/{}", name).parse().unwrap())
+        },
+    )
+}
+
+fn block_texture() -> impl Strategy<Value = BlockTexture> {
+    (
+        face("x"),
+        face("nx"),
+        face("y"),
+        face("ny"),
+        face("z"),
+        face("nz"),
+    )
+        .prop_map(|(x, nx, y, ny, z, nz)| BlockTexture {
+            x,
+            nx,
+            y,
+            ny,
+            z,
+            nz,
+        })
+}
+
+fn faces(t: &BlockTexture) -> [&FaceTexture; 6] {
+    [&t.x, &t.nx, &t.y, &t.ny, &t.z, &t.nz]
+}
+
+#[test]
+fn there_are_24_rotations() {
+    let all = CubeRotation::all();
+
+    assert_eq!(all.len(), 24);
+    assert_eq!(all.iter().collect::<HashSet<_>>().len(), 24);
+    assert_eq!(all[0], CubeRotation::IDENTITY);
+}
+
+#[test]
+fn quarter_turns_round_trip() {
+    for r in CubeRotation::all() {
+        let [x, y, z] = r.to_xyz();
+        assert_eq!(CubeRotation::from_xyz(x, y, z), r);
+    }
+}
+
+proptest! {
+    #[test]
+    fn rotations_compose(
+        texture in block_texture(),
+        a in cube_rotation(),
+        b in cube_rotation(),
+        uvlock in any::<bool>(),
+    ) {
+        prop_assert_eq!(
+            texture.clone().rotate(a, uvlock).rotate(b, uvlock),
+            texture.rotate(a.then(b), uvlock)
+        );
+    }
+
+    #[test]
+    fn inverse_turns_back(
+        texture in block_texture(),
+        r in cube_rotation(),
+        uvlock in any::<bool>(),
+    ) {
+        prop_assert_eq!(r.then(r.inverse()), CubeRotation::IDENTITY);
+        prop_assert_eq!(
+            texture.clone().rotate(r, uvlock).rotate(r.inverse(), uvlock),
+            texture
+        );
+    }
+
+    #[test]
+    fn blockstate_rotations_match_quarter_turns(
+        texture in block_texture(),
+        x in rotation(),
+        y in rotation(),
+        z in rotation(),
+        uvlock in any::<bool>(),
+    ) {
+        prop_assert_eq!(
+            texture
+                .clone()
+                .rotate_x(x, uvlock)
+                .rotate_y(y, uvlock)
+                .rotate_z(z, uvlock),
+            texture.rotate(CubeRotation::from_xyz(x, y, z), uvlock)
+        );
+    }
+
+    #[test]
+    fn flips_are_kept(
+        texture in block_texture(),
+        r in cube_rotation(),
+        uvlock in any::<bool>(),
+    ) {
+        let rotated = texture.clone().rotate(r, uvlock);
+
+        for face in faces(&texture) {
+            let moved = faces(&rotated)
+                .into_iter()
+                .find(|f| f.path == face.path)
+                .unwrap();
+            prop_assert_eq!((moved.flip_x, moved.flip_y), (face.flip_x, face.flip_y));
+        }
+    }
+
+    #[test]
+    fn face_strings_round_trip(
+        texture in block_texture(),
+        r in cube_rotation(),
+        uvlock in any::<bool>(),
+    ) {
+        let rotated = texture.rotate(r, uvlock);
+
+        for face in faces(&rotated) {
+            let parsed: FaceTexture = face.to_string().parse().unwrap();
+            prop_assert_eq!(&parsed, face);
+        }
+    }
+}
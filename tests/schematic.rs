@@ -0,0 +1,189 @@
+use std::{collections::BTreeMap, io::Read};
+
+use flate2::read::GzDecoder;
+use minecraft_blocks::{
+    art::{BlockArt, Facing},
+    schematic::{DATA_VERSION, block_state, write_schematic},
+};
+
+#[derive(Debug, PartialEq)]
+enum Tag {
+    Short(i16),
+    Int(i32),
+    ByteArray(Vec<u8>),
+    Compound(BTreeMap<String, Tag>),
+    IntArray(Vec<i32>),
+}
+
+/// Reads the named tags of an uncompressed NBT compound, after its type and name.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        bytes.try_into().unwrap()
+    }
+
+    fn int(&mut self) -> i32 {
+        i32::from_be_bytes(self.take())
+    }
+
+    fn name(&mut self) -> String {
+        let len = u16::from_be_bytes(self.take()) as usize;
+        let (name, rest) = self.0.split_at(len);
+        self.0 = rest;
+        String::from_utf8(name.to_vec()).unwrap()
+    }
+
+    fn compound(&mut self) -> BTreeMap<String, Tag> {
+        let mut tags = BTreeMap::new();
+        loop {
+            let [tag] = self.take();
+            if tag == 0 {
+                return tags;
+            }
+            let name = self.name();
+            let value = match tag {
+                2 => Tag::Short(i16::from_be_bytes(self.take())),
+                3 => Tag::Int(self.int()),
+                7 => {
+                    let len = self.int() as usize;
+                    Tag::ByteArray((0..len).map(|_| self.take::<1>()[0]).collect())
+                }
+                10 => Tag::Compound(self.compound()),
+                11 => {
+                    let len = self.int() as usize;
+                    Tag::IntArray((0..len).map(|_| self.int()).collect())
+                }
+                _ => panic!("unexpected tag {}", tag),
+            };
+            assert!(tags.insert(name, value).is_none());
+        }
+    }
+}
+
+fn read_schematic(art: &BlockArt) -> BTreeMap<String, Tag> {
+    let mut bytes = Vec::new();
+    write_schematic(art, DATA_VERSION, &mut bytes).unwrap();
+    let mut nbt = Vec::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut nbt)
+        .unwrap();
+
+    let mut reader = Reader(&nbt);
+    assert_eq!(reader.take(), [10]);
+    assert_eq!(reader.name(), "Schematic");
+    let tags = reader.compound();
+    assert!(reader.0.is_empty());
+    tags
+}
+
+fn varints(mut bytes: &[u8]) -> Vec<u32> {
+    let mut values = Vec::new();
+    while !bytes.is_empty() {
+        let (mut value, mut shift) = (0, 0);
+        loop {
+            let byte = bytes[0];
+            bytes = &bytes[1..];
+            value |= ((byte & 0x7f) as u32) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        values.push(value);
+    }
+    values
+}
+
+#[test]
+fn block_states_of_materials() {
+    assert_eq!(block_state("minecraft:stone"), "minecraft:stone");
+    assert_eq!(block_state("minecraft:stone#"), "minecraft:stone");
+    assert_eq!(
+        block_state("minecraft:oak_log#axis=x"),
+        "minecraft:oak_log[axis=x]"
+    );
+    assert_eq!(
+        block_state("minecraft:oak_stairs#facing=east,half=top"),
+        "minecraft:oak_stairs[facing=east,half=top]"
+    );
+    assert_eq!(block_state("minecraft:stone@2"), "minecraft:stone");
+    assert_eq!(block_state("minecraft:stone#@0"), "minecraft:stone");
+    assert_eq!(
+        block_state("minecraft:oak_log#axis=y@1"),
+        "minecraft:oak_log[axis=y]"
+    );
+}
+
+#[test]
+fn schematic_layout() {
+    let art = BlockArt {
+        width: 2,
+        height: 2,
+        facing: Facing::South,
+        rows: vec![
+            vec![Some("minecraft:stone".to_owned()), None],
+            vec![
+                Some("minecraft:oak_log#axis=x".to_owned()),
+                Some("minecraft:stone@1".to_owned()),
+            ],
+        ],
+    };
+    let tags = read_schematic(&art);
+
+    assert_eq!(tags["Version"], Tag::Int(2));
+    assert_eq!(tags["DataVersion"], Tag::Int(DATA_VERSION));
+    assert_eq!(tags["Width"], Tag::Short(2));
+    assert_eq!(tags["Height"], Tag::Short(2));
+    assert_eq!(tags["Length"], Tag::Short(1));
+    assert_eq!(tags["Offset"], Tag::IntArray(vec![0, 0, 0]));
+    assert_eq!(tags["PaletteMax"], Tag::Int(3));
+    assert_eq!(
+        tags["Palette"],
+        Tag::Compound(BTreeMap::from([
+            ("minecraft:air".to_owned(), Tag::Int(0)),
+            ("minecraft:stone".to_owned(), Tag::Int(1)),
+            ("minecraft:oak_log[axis=x]".to_owned(), Tag::Int(2)),
+        ]))
+    );
+    // x fastest, then z, then y from the bottom: the bottom row first
+    assert_eq!(tags["BlockData"], Tag::ByteArray(vec![2, 1, 1, 0]));
+}
+
+#[test]
+fn palette_indices_above_127_take_two_bytes() {
+    let ids = (0..200).map(|i| Some(format!("minecraft:block_{}", i)));
+    let art = BlockArt {
+        width: 200,
+        height: 1,
+        facing: Facing::Up,
+        rows: vec![ids.collect()],
+    };
+    let tags = read_schematic(&art);
+
+    assert_eq!(tags["PaletteMax"], Tag::Int(201));
+    let Tag::ByteArray(data) = &tags["BlockData"] else {
+        panic!("BlockData is not a byte array");
+    };
+    // 127 indices of 1 byte and 73 of 2 bytes
+    assert_eq!(data.len(), 127 + 73 * 2);
+    assert_eq!(data[126], 127);
+    assert_eq!(data[127..129], [0x80, 0x01]);
+    assert_eq!(data[data.len() - 2..], [0xc8, 0x01]);
+    assert_eq!(varints(data), (1..=200).collect::<Vec<u32>>());
+}
+
+#[test]
+fn art_too_large_for_a_schematic_is_an_error() {
+    let art = BlockArt {
+        width: 70_000,
+        height: 1,
+        facing: Facing::South,
+        rows: vec![vec![None; 70_000]],
+    };
+
+    let error = write_schematic(&art, DATA_VERSION, Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,171 @@
+use minecraft_blocks::{
+    palette::Rotation,
+    schema::model::ElementRotation,
+    shapes::{Cuboid, Shape, classify},
+};
+use serde_json::json;
+
+fn cuboid(from: [f32; 3], to: [f32; 3]) -> Cuboid {
+    Cuboid { from, to }
+}
+
+fn rotation(axis: &str, angle: f32) -> ElementRotation {
+    serde_json::from_value(json!({"origin": [8, 8, 8], "axis": axis, "angle": angle})).unwrap()
+}
+
+fn shape(cuboids: &[Cuboid]) -> Shape {
+    let elements: Vec<(Cuboid, Option<&ElementRotation>)> =
+        cuboids.iter().map(|c| (*c, None)).collect();
+    classify(&elements)
+}
+
+const BOTTOM_SLAB: Cuboid = Cuboid {
+    from: [0.0, 0.0, 0.0],
+    to: [16.0, 8.0, 16.0],
+};
+
+#[test]
+fn empty_and_full_blocks() {
+    assert_eq!(shape(&[]), Shape::Empty);
+    assert_eq!(shape(&[cuboid([0.0; 3], [16.0; 3])]), Shape::FullCube);
+    // Overlays like grass sides repeat the cube
+    assert_eq!(
+        shape(&[cuboid([0.0; 3], [16.0; 3]), cuboid([0.0; 3], [16.0; 3])]),
+        Shape::FullCube
+    );
+}
+
+#[test]
+fn slabs() {
+    assert_eq!(shape(&[BOTTOM_SLAB]), Shape::BottomSlab);
+    assert_eq!(
+        shape(&[cuboid([0.0, 8.0, 0.0], [16.0, 16.0, 16.0])]),
+        Shape::TopSlab
+    );
+    // Packs often offset coordinates slightly
+    assert_eq!(
+        shape(&[cuboid([0.001, 0.0, 0.0], [15.999, 8.001, 16.0])]),
+        Shape::BottomSlab
+    );
+    // Turned upside down by the blockstate
+    assert_eq!(
+        shape(&[BOTTOM_SLAB.rotated([Rotation::CCW180, Rotation::CCW0, Rotation::CCW0])]),
+        Shape::TopSlab
+    );
+}
+
+#[test]
+fn stairs() {
+    let straight = [BOTTOM_SLAB, cuboid([0.0, 8.0, 8.0], [16.0, 16.0, 16.0])];
+    assert_eq!(shape(&straight), Shape::Stair);
+
+    let inner = [
+        BOTTOM_SLAB,
+        cuboid([0.0, 8.0, 8.0], [16.0, 16.0, 16.0]),
+        cuboid([0.0, 8.0, 0.0], [8.0, 16.0, 8.0]),
+    ];
+    assert_eq!(shape(&inner), Shape::Stair);
+
+    let outer = [BOTTOM_SLAB, cuboid([8.0, 8.0, 8.0], [16.0, 16.0, 16.0])];
+    assert_eq!(shape(&outer), Shape::Stair);
+
+    // The step has to stay in the other half of the block
+    let wall = [BOTTOM_SLAB, cuboid([0.0, 0.0, 8.0], [16.0, 16.0, 16.0])];
+    assert_eq!(shape(&wall), Shape::Other);
+
+    let upside_down =
+        straight.map(|c| c.rotated([Rotation::CCW180, Rotation::CCW90, Rotation::CCW0]));
+    assert_eq!(shape(&upside_down), Shape::Stair);
+}
+
+#[test]
+fn layers() {
+    assert_eq!(
+        shape(&[cuboid([0.0; 3], [16.0, 1.0, 16.0])]),
+        Shape::Layer { height: 1.0 }
+    );
+    // Snow of 3 layers
+    assert_eq!(
+        shape(&[cuboid([0.0; 3], [16.0, 6.0, 16.0])]),
+        Shape::Layer { height: 6.0 }
+    );
+    // Snow of 4 layers is as high as a slab
+    assert_eq!(
+        shape(&[cuboid([0.0; 3], [16.0, 8.0, 16.0])]),
+        Shape::BottomSlab
+    );
+    // Layers do not float
+    assert_eq!(
+        shape(&[cuboid([0.0, 1.0, 0.0], [16.0, 2.0, 16.0])]),
+        Shape::Other
+    );
+}
+
+#[test]
+fn panes() {
+    let post = cuboid([7.0, 0.0, 7.0], [9.0, 16.0, 9.0]);
+    assert_eq!(shape(&[post]), Shape::Pane);
+    assert_eq!(
+        shape(&[
+            post,
+            cuboid([7.0, 0.0, 0.0], [9.0, 16.0, 7.0]),
+            cuboid([9.0, 0.0, 7.0], [16.0, 16.0, 9.0]),
+        ]),
+        Shape::Pane
+    );
+
+    // Fence posts are too thick
+    assert_eq!(
+        shape(&[cuboid([6.0, 0.0, 6.0], [10.0, 16.0, 10.0])]),
+        Shape::Other
+    );
+    // Bars that do not reach the top of the block
+    assert_eq!(
+        shape(&[cuboid([7.0, 0.0, 7.0], [9.0, 12.0, 9.0])]),
+        Shape::Other
+    );
+}
+
+#[test]
+fn crosses() {
+    let (plus, minus) = (rotation("y", 45.0), rotation("y", -45.0));
+    let plane = |z: f32| cuboid([0.8, 0.0, z], [15.2, 16.0, z]);
+
+    assert_eq!(
+        classify(&[(plane(8.0), Some(&plus)), (plane(8.0), Some(&minus))]),
+        Shape::Cross
+    );
+
+    // Crossed planes that are tilted another way are something else
+    let tilted = rotation("x", 45.0);
+    assert_eq!(
+        classify(&[(plane(8.0), Some(&plus)), (plane(8.0), Some(&tilted))]),
+        Shape::Other
+    );
+    // So is a cross with an upright element
+    assert_eq!(
+        classify(&[(plane(8.0), Some(&plus)), (plane(8.0), None)]),
+        Shape::Other
+    );
+}
+
+#[test]
+fn other_shapes() {
+    // Tilted elements, like a lever handle
+    let tilted = rotation("x", 22.5);
+    assert_eq!(
+        classify(&[(cuboid([7.0, 0.0, 7.0], [9.0, 10.0, 9.0]), Some(&tilted))]),
+        Shape::Other
+    );
+    // Unrotated elements are as good as none
+    let zero = rotation("z", 0.0);
+    assert_eq!(
+        classify(&[(cuboid([0.0; 3], [16.0; 3]), Some(&zero))]),
+        Shape::FullCube
+    );
+    // Blocks narrower than the whole block, like chests
+    assert_eq!(
+        shape(&[cuboid([1.0, 0.0, 1.0], [15.0, 14.0, 15.0])]),
+        Shape::Other
+    );
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{MemorySource, image};
+use minecraft_blocks::{
+    Diagnostics,
+    palette::Color,
+    schema::{blockstate::parse_state, location::ResourceLocation},
+    tint::{
+        ATTACHED_STEM, BIRCH_FOLIAGE, Climate, LILY_PAD, SPRUCE_FOLIAGE, TintSource, Tints, WATER,
+        redstone_color, stem_color, tint_source,
+    },
+};
+
+fn block(name: &str) -> ResourceLocation {
+    name.parse().unwrap()
+}
+
+fn source_of(name: &str) -> Option<TintSource> {
+    tint_source(&block(name), &BTreeMap::new())
+}
+
+/// Colormap of 256×256 pixels whose red is the column and green the row, blue telling
+/// grass from foliage.
+fn colormap(blue: u8) -> image::RgbaImage {
+    image(256, 256, |x, y| [x as u8, y as u8, blue, 255])
+}
+
+fn colormaps() -> MemorySource {
+    MemorySource::default()
+        .with_texture("colormap/grass", &colormap(1))
+        .with_texture("colormap/foliage", &colormap(2))
+}
+
+#[test]
+fn redstone_wire_darkens_without_power() {
+    assert_eq!(redstone_color(0), Color::rgb(0x4c0000));
+    assert_eq!(redstone_color(15), Color::rgb(0xff3200));
+    // Brighter with every level of power
+    for power in 1..=15 {
+        assert!(redstone_color(power).r > redstone_color(power - 1).r);
+    }
+    assert_eq!(redstone_color(200), redstone_color(15));
+
+    let powered = parse_state("east=side,north=none,power=15,south=none,west=side");
+    assert_eq!(
+        tint_source(&block("minecraft:redstone_wire"), &powered),
+        Some(TintSource::Fixed(redstone_color(15)))
+    );
+    assert_eq!(
+        source_of("minecraft:redstone_wire"),
+        Some(TintSource::Fixed(redstone_color(0)))
+    );
+}
+
+#[test]
+fn stems_ripen_with_age() {
+    assert_eq!(stem_color(0), Color::rgb(0x00ff00));
+    assert_eq!(stem_color(7), Color::rgb(0xe0c71c));
+    assert_eq!(stem_color(9), stem_color(7));
+
+    let growing = parse_state("age=3");
+    assert_eq!(
+        tint_source(&block("minecraft:melon_stem"), &growing),
+        Some(TintSource::Fixed(Color::rgb(0x60e70c)))
+    );
+    assert_eq!(
+        source_of("minecraft:pumpkin_stem"),
+        Some(TintSource::Fixed(stem_color(0)))
+    );
+    // Attached stems look fully grown whatever their facing
+    let attached = parse_state("facing=north");
+    for stem in [
+        "minecraft:attached_pumpkin_stem",
+        "minecraft:attached_melon_stem",
+    ] {
+        assert_eq!(
+            tint_source(&block(stem), &attached),
+            Some(TintSource::Fixed(ATTACHED_STEM))
+        );
+    }
+    assert_eq!(ATTACHED_STEM, stem_color(7));
+}
+
+#[test]
+fn blocks_are_tinted_like_the_game_tints_them() {
+    assert_eq!(source_of("minecraft:grass_block"), Some(TintSource::Grass));
+    assert_eq!(source_of("minecraft:large_fern"), Some(TintSource::Grass));
+    assert_eq!(source_of("minecraft:oak_leaves"), Some(TintSource::Foliage));
+    assert_eq!(source_of("minecraft:vine"), Some(TintSource::Foliage));
+    assert_eq!(
+        source_of("minecraft:birch_leaves"),
+        Some(TintSource::Fixed(BIRCH_FOLIAGE))
+    );
+    assert_eq!(
+        source_of("minecraft:lily_pad"),
+        Some(TintSource::Fixed(LILY_PAD))
+    );
+    assert_eq!(
+        source_of("minecraft:water_cauldron"),
+        Some(TintSource::Fixed(WATER))
+    );
+
+    assert_eq!(source_of("minecraft:stone"), None);
+    // Leaves of modded blocks have block colors of their own
+    assert_eq!(source_of("example:oak_leaves"), None);
+}
+
+#[test]
+fn colormaps_are_sampled_at_the_plains_climate() {
+    let mut diagnostics = Diagnostics::default();
+    let tints = Tints::load(&colormaps(), &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    assert_eq!(tints.climate, Climate::PLAINS);
+
+    // Temperature 0.8 and downfall 0.4 × 0.8, as the game rounds them
+    let state = BTreeMap::new();
+    assert_eq!(
+        tints.color(&block("minecraft:grass_block"), &state),
+        Some(Color::rgb(0x32ad01))
+    );
+    assert_eq!(
+        tints.color(&block("minecraft:oak_leaves"), &state),
+        Some(Color::rgb(0x32ad02))
+    );
+    assert_eq!(
+        tints.color(&block("minecraft:spruce_leaves"), &state),
+        Some(SPRUCE_FOLIAGE)
+    );
+    assert_eq!(tints.color(&block("minecraft:stone"), &state), None);
+}
+
+#[test]
+fn climates_are_clamped_to_the_colormap() {
+    let mut tints = Tints::load(&colormaps(), &mut Diagnostics::default());
+    let grass = |tints: &Tints| {
+        tints
+            .color(&block("minecraft:grass_block"), &BTreeMap::new())
+            .unwrap()
+    };
+
+    // Hot and dry, like deserts, is the bottom left corner
+    tints.climate = Climate {
+        temperature: 2.0,
+        downfall: 0.0,
+    };
+    assert_eq!(grass(&tints), Color::rgb(0x00ff01));
+
+    // Cold climates have no downfall left, whatever it is
+    tints.climate = Climate {
+        temperature: -0.5,
+        downfall: 0.5,
+    };
+    assert_eq!(grass(&tints), Color::rgb(0xffff01));
+
+    tints.climate = Climate {
+        temperature: 1.0,
+        downfall: 1.0,
+    };
+    assert_eq!(grass(&tints), Color::rgb(0x000001));
+}
+
+#[test]
+fn missing_colormaps_leave_blocks_untinted() {
+    let source = MemorySource::default().with_texture("colormap/foliage", &colormap(2));
+    let mut diagnostics = Diagnostics::default();
+    let tints = Tints::load(&source, &mut diagnostics);
+    assert!(diagnostics.is_empty());
+
+    let state = BTreeMap::new();
+    assert_eq!(tints.color(&block("minecraft:grass_block"), &state), None);
+    assert!(
+        tints
+            .color(&block("minecraft:oak_leaves"), &state)
+            .is_some()
+    );
+    // Fixed colors need no colormap
+    assert_eq!(tints.color(&block("minecraft:water"), &state), Some(WATER));
+
+    // Colormaps that are not images are reported as well
+    let source = colormaps().with_file("assets/minecraft/textures/colormap/grass.png", "grass");
+    let mut diagnostics = Diagnostics::default();
+    let tints = Tints::load(&source, &mut diagnostics);
+    assert_eq!(diagnostics.errors().len(), 1);
+    assert_eq!(tints.color(&block("minecraft:grass_block"), &state), None);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::fs;
+
+use common::MemorySource;
+use minecraft_blocks::{
+    AssetStore, Diagnostics,
+    palette::{BlockVolume, MaterialDisplay, Rotation},
+    source::LayeredSource,
+};
+
+/// A block filling the north half of the model, turned to face each direction.
+fn half_block() -> MemorySource {
+    MemorySource::default()
+        .with_file(
+            "assets/minecraft/blockstates/half.json",
+            r#"{"variants": {
+                "facing=east": {"model": "block/half", "y": 90},
+                "facing=north": {"model": "block/half"},
+                "facing=up": {"model": "block/half", "x": 270}
+            }}"#,
+        )
+        .with_file(
+            "assets/minecraft/models/block/half.json",
+            r##"{"elements": [{
+                "from": [0, 0, 0],
+                "to": [16, 16, 8],
+                "faces": {"north": {"texture": "block/stone"}}
+            }]}"##,
+        )
+}
+
+fn load(source: MemorySource, diagnostics: &mut Diagnostics) -> AssetStore {
+    let mut layers = LayeredSource::default();
+    layers.push("volumes".to_owned(), Box::new(source));
+    AssetStore::load(layers, diagnostics).unwrap()
+}
+
+fn volume(display: &MaterialDisplay) -> &BlockVolume {
+    match display {
+        MaterialDisplay::Volume(volume) => volume,
+        _ => panic!("not a volume"),
+    }
+}
+
+#[test]
+fn volumes_turn_counter_clockwise() {
+    let mut diagnostics = Diagnostics::default();
+    let assets = load(half_block(), &mut diagnostics);
+    let set = assets.volume_materials(4, false, &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    // The game turns y clockwise looking down, a quarter turn the other way
+    let east = volume(&set.materials["minecraft:half#facing=east"].display);
+    assert_eq!(
+        [east.rotation_x, east.rotation_y, east.rotation_z],
+        [Rotation::CCW0, Rotation::CCW270, Rotation::CCW0]
+    );
+    let grid = set.volumes[&east.path].rotated([east.rotation_x, east.rotation_y, east.rotation_z]);
+    assert!(grid.voxels().all(|([x, _, _], _)| x >= 2));
+
+    let up = volume(&set.materials["minecraft:half#facing=up"].display);
+    assert_eq!(up.rotation_x, Rotation::CCW90);
+    let grid = set.volumes[&up.path].rotated([up.rotation_x, up.rotation_y, up.rotation_z]);
+    assert!(grid.voxels().all(|([_, y, _], _)| y >= 2));
+
+    // Every facing shares the unturned grid
+    assert_eq!(set.volumes.len(), 1);
+}
+
+#[test]
+fn multipart_volumes_have_file_safe_paths() {
+    let source = half_block().with_file(
+        "assets/minecraft/blockstates/wall.json",
+        r#"{"multipart": [
+            {"apply": {"model": "block/half"}},
+            {"when": {"east": "low|tall"}, "apply": {"model": "block/half", "y": 90}},
+            {"when": {"north": "low|tall"}, "apply": {"model": "block/half", "y": 180}}
+        ]}"#,
+    );
+    let mut diagnostics = Diagnostics::default();
+    let assets = load(source, &mut diagnostics);
+    let set = assets.volume_materials(4, false, &mut diagnostics);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+
+    // The state stays in the material id only
+    let wall = volume(&set.materials["minecraft:wall#east=tall,north=low"].display);
+    assert_eq!(wall.path, "minecraft/wall/east-tall.north-low");
+    for path in set.volumes.keys() {
+        assert!(!path.contains(['#', '=', ',']), "{}", path);
+    }
+
+    let output_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("volume_paths");
+    let _ = fs::remove_dir_all(&output_dir);
+    assert_eq!(set.save_volumes(&output_dir).unwrap(), set.volumes.len());
+    assert!(
+        output_dir
+            .join("minecraft/wall/east-tall.north-low.voxel")
+            .is_file()
+    );
+}
@@ -0,0 +1,170 @@
+use std::io::ErrorKind;
+
+use minecraft_blocks::voxel::{FaceSample, MAX_GRID_SIZE, Voxel, VoxelGrid};
+
+fn sample(texture: u16, uv: [f32; 2], tinted: bool) -> Option<FaceSample> {
+    Some(FaceSample {
+        texture,
+        uv,
+        tinted,
+    })
+}
+
+fn grid() -> VoxelGrid {
+    let mut grid = VoxelGrid::new(4);
+    let stone = grid.texture_index(&"minecraft:block/stone".parse().unwrap());
+    let grass = grid.texture_index(&"minecraft:block/grass_block_top".parse().unwrap());
+
+    grid.set(
+        [0, 0, 0],
+        Some(Voxel {
+            faces: [
+                sample(stone, [0.0, 0.0], false),
+                None,
+                sample(grass, [15.5, 0.25], true),
+                None,
+                None,
+                sample(stone, [1.0 / 256.0, 255.0], false),
+            ],
+        }),
+    );
+    // A voxel without visible faces, and runs of filled cells across rows
+    grid.set([1, 0, 0], Some(Voxel::default()));
+    for x in 0..4 {
+        grid.set(
+            [x, 3, 2],
+            Some(Voxel {
+                faces: [
+                    None,
+                    None,
+                    sample(grass, [x as f32 * 4.0, 8.0], true),
+                    None,
+                    None,
+                    None,
+                ],
+            }),
+        );
+    }
+    grid.set([0, 0, 3], Some(Voxel::default()));
+    grid.set([3, 3, 3], Some(Voxel::default()));
+    grid
+}
+
+fn bytes(grid: &VoxelGrid) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    grid.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn grids_round_trip() {
+    for grid in [grid(), VoxelGrid::new(0), VoxelGrid::new(3)] {
+        let read = VoxelGrid::read_from(bytes(&grid).as_slice()).unwrap();
+        assert_eq!(read, grid);
+    }
+
+    let mut full = VoxelGrid::new(2);
+    for (x, y, z) in (0..8).map(|i| (i % 2, i / 2 % 2, i / 4)) {
+        full.set([x, y, z], Some(Voxel::default()));
+    }
+    assert_eq!(VoxelGrid::read_from(bytes(&full).as_slice()).unwrap(), full);
+
+    let mut largest = VoxelGrid::new(MAX_GRID_SIZE);
+    largest.set([MAX_GRID_SIZE - 1; 3], Some(Voxel::default()));
+    assert_eq!(
+        VoxelGrid::read_from(bytes(&largest).as_slice()).unwrap(),
+        largest
+    );
+}
+
+#[test]
+fn header_layout() {
+    let bytes = bytes(&grid());
+
+    assert_eq!(&bytes[..4], b"MBVX");
+    assert_eq!(bytes[4], 2);
+    assert_eq!(bytes[5..7], 4u16.to_le_bytes());
+    assert_eq!(bytes[7..9], 2u16.to_le_bytes());
+    let name = "minecraft:block/stone";
+    assert_eq!(bytes[9..11], (name.len() as u16).to_le_bytes());
+    assert_eq!(&bytes[11..11 + name.len()], name.as_bytes());
+}
+
+/// A version 1 file of one voxel: no tint masks.
+fn version_1() -> Vec<u8> {
+    let name = "minecraft:block/stone";
+    let mut bytes = b"MBVX".to_vec();
+    bytes.push(1);
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend((name.len() as u16).to_le_bytes());
+    bytes.extend(name.as_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(1u32.to_le_bytes());
+    // Up face only, texture 0 at (8, 4)
+    bytes.push(0b100);
+    bytes.extend(0u16.to_le_bytes());
+    bytes.extend((8u16 * 256).to_le_bytes());
+    bytes.extend((4u16 * 256).to_le_bytes());
+    bytes
+}
+
+#[test]
+fn version_1_samples_are_untinted() {
+    let grid = VoxelGrid::read_from(version_1().as_slice()).unwrap();
+
+    assert_eq!(grid.size, 1);
+    assert_eq!(grid.textures[0].to_string(), "minecraft:block/stone");
+    assert_eq!(
+        grid.get([0, 0, 0]),
+        Some(&Voxel {
+            faces: [None, None, sample(0, [8.0, 4.0], false), None, None, None],
+        })
+    );
+}
+
+fn rejected(bytes: &[u8]) -> std::io::Error {
+    VoxelGrid::read_from(bytes).unwrap_err()
+}
+
+#[test]
+fn invalid_files_are_rejected() {
+    let valid = bytes(&grid());
+
+    let mut magic = valid.clone();
+    magic[..4].copy_from_slice(b"MBVY");
+    assert_eq!(rejected(&magic).kind(), ErrorKind::InvalidData);
+
+    for version in [0, 3, 255] {
+        let mut file = valid.clone();
+        file[4] = version;
+        let error = rejected(&file);
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("version"), "{}", error);
+    }
+
+    let mut size = valid.clone();
+    size[5..7].copy_from_slice(&(MAX_GRID_SIZE as u16 + 1).to_le_bytes());
+    assert_eq!(rejected(&size).kind(), ErrorKind::InvalidData);
+
+    // A run of more cells than the grid has
+    let mut cells = version_1();
+    let runs = cells.len() - 15;
+    cells[runs..runs + 4].copy_from_slice(&1u32.to_le_bytes());
+    assert_eq!(rejected(&cells).kind(), ErrorKind::InvalidData);
+
+    // A sample of a texture the file does not list
+    let mut texture = version_1();
+    let index = texture.len() - 6;
+    texture[index..index + 2].copy_from_slice(&1u16.to_le_bytes());
+    assert_eq!(rejected(&texture).kind(), ErrorKind::InvalidData);
+
+    for len in [0, 3, 9, valid.len() - 1] {
+        assert_eq!(
+            rejected(&valid[..len]).kind(),
+            ErrorKind::UnexpectedEof,
+            "{} bytes",
+            len
+        );
+    }
+}
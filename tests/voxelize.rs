@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use minecraft_blocks::{
+    Error,
+    baked::BakedModel,
+    voxel::{FACE_NAMES, VoxelGrid, voxelize},
+};
+use serde_json::json;
+
+/// Model of `elements` given as block model JSON.
+fn model(elements: serde_json::Value) -> BakedModel {
+    BakedModel {
+        name: "minecraft:block/test".parse().unwrap(),
+        ambientocclusion: true,
+        display: HashMap::new(),
+        textures: HashMap::new(),
+        elements: serde_json::from_value(elements).unwrap(),
+    }
+}
+
+/// Every face of an element showing `texture`.
+fn faces(texture: &str) -> serde_json::Value {
+    let face = json!({ "texture": texture });
+    FACE_NAMES
+        .iter()
+        .map(|name| (name.to_string(), face.clone()))
+        .collect()
+}
+
+fn filled(grid: &VoxelGrid) -> usize {
+    grid.voxels().count()
+}
+
+/// Names of the faces of the voxel at `pos` that have a sample.
+fn visible_faces(grid: &VoxelGrid, pos: [usize; 3]) -> Vec<&'static str> {
+    let voxel = grid.get(pos).unwrap();
+    FACE_NAMES
+        .iter()
+        .zip(voxel.faces)
+        .filter(|(_, sample)| sample.is_some())
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+#[test]
+fn bottom_slab_fills_the_lower_half() {
+    let slab = model(json!([{
+        "from": [0, 0, 0],
+        "to": [16, 8, 16],
+        "faces": faces("minecraft:block/stone"),
+    }]));
+    let grid = voxelize(&slab, 4).unwrap();
+
+    assert_eq!(grid.size, 4);
+    assert_eq!(filled(&grid), 32);
+    assert!(grid.voxels().all(|([_, y, _], _)| y < 2));
+    assert_eq!(grid.textures.len(), 1);
+    assert!(!grid.is_tinted());
+
+    // Faces between voxels are hidden
+    assert_eq!(visible_faces(&grid, [0, 1, 0]), ["west", "up", "north"]);
+    assert_eq!(visible_faces(&grid, [1, 0, 2]), ["down"]);
+    assert_eq!(visible_faces(&grid, [3, 0, 3]), ["east", "down", "south"]);
+    assert_eq!(visible_faces(&grid, [1, 1, 1]), ["up"]);
+
+    // Samples are taken at the voxel centers, across the UV region of the face
+    let up = grid.get([0, 1, 3]).unwrap().faces[2].unwrap();
+    assert_eq!(up.uv, [2.0, 14.0]);
+    let west = grid.get([0, 1, 3]).unwrap().faces[1].unwrap();
+    assert_eq!(west.uv, [14.0, 10.0]);
+}
+
+#[test]
+fn tinted_faces_are_marked() {
+    let mut faces = faces("minecraft:block/grass_block_side");
+    faces["up"] = json!({ "texture": "minecraft:block/grass_block_top", "tintindex": 0 });
+    let grid = voxelize(
+        &model(json!([{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": faces }])),
+        2,
+    )
+    .unwrap();
+
+    assert!(grid.is_tinted());
+    assert_eq!(grid.textures.len(), 2);
+    let voxel = grid.get([0, 1, 0]).unwrap();
+    assert!(voxel.faces[2].unwrap().tinted);
+    assert!(!voxel.faces[1].unwrap().tinted);
+}
+
+/// Element of 8×16×8 pixels in the middle of the block, turned around y by `angle`.
+fn pillar(angle: f32, rescale: bool) -> BakedModel {
+    model(json!([{
+        "from": [4, 0, 4],
+        "to": [12, 16, 12],
+        "rotation": { "origin": [8, 8, 8], "axis": "y", "angle": angle, "rescale": rescale },
+        "faces": faces("minecraft:block/stone"),
+    }]))
+}
+
+#[test]
+fn elements_turned_by_45_degrees_fill_a_diamond() {
+    let grid = voxelize(&pillar(45.0, false), 16).unwrap();
+
+    // The corners of the square turn onto the axes, where voxel centers up to
+    // 4√2 ≈ 5.7 pixels from the middle in x and z together are inside
+    assert!(grid.get([12, 8, 8]).is_some());
+    assert!(grid.get([3, 8, 7]).is_some());
+    assert!(grid.get([8, 8, 12]).is_some());
+    assert!(grid.get([13, 8, 8]).is_none());
+    // And the sides turn away from the corners of the unturned square
+    assert!(grid.get([4, 8, 4]).is_none());
+    assert!(grid.get([11, 8, 11]).is_none());
+    assert!(grid.get([8, 8, 8]).is_some());
+
+    // A diamond as tall as the block, with the area of the square
+    let layer = grid.voxels().filter(|([_, y, _], _)| *y == 0).count();
+    assert!((56..=72).contains(&layer), "{} voxels", layer);
+    assert_eq!(filled(&grid), layer * 16);
+
+    let unturned = voxelize(&pillar(0.0, false), 16).unwrap();
+    assert!(unturned.get([4, 8, 4]).is_some());
+    assert!(unturned.get([13, 8, 8]).is_none());
+    assert_eq!(filled(&unturned), 64 * 16);
+}
+
+#[test]
+fn rescaled_elements_keep_their_corners_in_reach() {
+    let grid = voxelize(&pillar(45.0, true), 16).unwrap();
+
+    // Scaled by √2 across the axis, the corners reach 8 pixels from the middle
+    assert!(grid.get([14, 8, 8]).is_some());
+    assert!(grid.get([1, 8, 8]).is_some());
+    assert!(grid.get([4, 8, 4]).is_some());
+    assert!(grid.get([1, 8, 1]).is_none());
+    assert!(grid.get([13, 8, 13]).is_none());
+}
+
+#[test]
+fn rotations_beyond_45_degrees_are_an_error() {
+    let result = voxelize(&pillar(67.5, false), 4);
+
+    assert!(matches!(
+        result,
+        Err(Error::InvalidElementRotation { angle, .. }) if angle == 67.5
+    ));
+}
+
+#[test]
+fn planes_thinner_than_a_voxel_fill_a_layer() {
+    let plane = model(json!([{
+        "from": [0, 0, 7],
+        "to": [16, 16, 7],
+        "faces": faces("minecraft:block/glass_pane"),
+    }]));
+    let grid = voxelize(&plane, 4).unwrap();
+
+    assert_eq!(filled(&grid), 16);
+    assert!(grid.voxels().all(|([_, _, z], _)| z == 1));
+}
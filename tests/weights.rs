@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use minecraft_blocks::{
+    AssetStore, Diagnostics, Error,
+    palette::{BlockIds, GroupRule},
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/weights");
+
+#[test]
+fn weighted_models_form_a_random_choice() {
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::open(&[FIXTURES], &mut diagnostics).unwrap();
+    let set = assets.full_cube_materials(false, &mut diagnostics);
+
+    let group = &set.groups["minecraft:stone"];
+    assert!(matches!(&group.block_ids, BlockIds::Blocks(ids) if ids.len() == 4));
+    assert_eq!(
+        group.rule,
+        GroupRule::RandomChoice {
+            weights: BTreeMap::from([
+                ("minecraft:stone@0".to_owned(), 3),
+                ("minecraft:stone@1".to_owned(), 1),
+                ("minecraft:stone@2".to_owned(), 1),
+                ("minecraft:stone@3".to_owned(), 1),
+            ])
+        }
+    );
+}
+
+#[test]
+fn weights_that_are_not_positive_are_reported() {
+    let mut diagnostics = Diagnostics::default();
+    let assets = AssetStore::open(&[FIXTURES], &mut diagnostics).unwrap();
+    assets.full_cube_materials(false, &mut diagnostics);
+
+    let weights: Vec<(String, i32)> = diagnostics
+        .errors()
+        .iter()
+        .map(|e| match e {
+            Error::InvalidWeight { model, weight, .. } => (model.to_string(), *weight),
+            other => panic!("unexpected diagnostic: {}", other),
+        })
+        .collect();
+    assert_eq!(
+        weights,
+        [
+            ("minecraft:block/stone_zero".to_owned(), 0),
+            ("minecraft:block/stone_negative".to_owned(), -2),
+        ]
+    );
+}
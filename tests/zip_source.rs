@@ -0,0 +1,100 @@
+mod common;
+
+use std::{
+    fs::File,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+};
+
+use common::{image, png};
+use minecraft_blocks::{
+    Diagnostics,
+    schema::{blockstate, location::ResourceLocation, model},
+    source::{self, AssetSource, ZipSource},
+    textures::read_texture_image,
+};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+/// Writes a resource pack with a single block to `name` in a scratch directory.
+fn pack(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    let options = SimpleFileOptions::default();
+
+    zip.add_directory("assets/minecraft/", options).unwrap();
+    let files = [
+        ("pack.mcmeta", br#"{"pack": {"pack_format": 34}}"#.to_vec()),
+        (
+            "assets/minecraft/blockstates/stone.json",
+            br#"{"variants": {"": {"model": "block/stone"}}}"#.to_vec(),
+        ),
+        (
+            "assets/minecraft/models/block/stone.json",
+            br#"{"parent": "block/cube_all", "textures": {"all": "block/stone"}}"#.to_vec(),
+        ),
+        (
+            "assets/minecraft/textures/block/stone.png",
+            png(&image(2, 2, |_, _| [125, 125, 125, 255])),
+        ),
+    ];
+    for (name, contents) in files {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(&contents).unwrap();
+    }
+    zip.finish().unwrap();
+
+    path
+}
+
+#[test]
+fn zips_list_and_read_entries() {
+    let path = pack("list.zip");
+    let zip = ZipSource::open(&path).unwrap();
+
+    assert_eq!(source::namespaces(&zip).unwrap(), ["minecraft"]);
+    assert_eq!(zip.list("").unwrap(), ["pack.mcmeta"]);
+    assert_eq!(
+        zip.list_dirs("assets/minecraft").unwrap(),
+        [
+            "assets/minecraft/blockstates",
+            "assets/minecraft/models",
+            "assets/minecraft/textures",
+        ]
+    );
+    // Directory entries are not files, trailing slashes are ignored
+    assert!(zip.list("assets").unwrap().is_empty());
+    assert_eq!(
+        zip.list("assets/minecraft/models/block/").unwrap(),
+        ["assets/minecraft/models/block/stone.json"]
+    );
+
+    assert!(zip.exists("assets/minecraft/blockstates/stone.json"));
+    assert!(!zip.exists("assets/minecraft/blockstates"));
+    assert_eq!(
+        zip.read("assets/minecraft/blockstates/dirt.json")
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+    assert_eq!(zip.describe(), path.display().to_string());
+}
+
+#[test]
+fn zips_are_read_like_directories() {
+    let zip = source::open(pack("assets.zip")).unwrap();
+    let stone: ResourceLocation = "minecraft:stone".parse().unwrap();
+
+    let mut diagnostics = Diagnostics::default();
+    let blockstates = blockstate::load_all(zip.as_ref(), &mut diagnostics).unwrap();
+    let models = model::load_all(zip.as_ref(), &mut diagnostics).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.errors());
+    assert_eq!(
+        blockstates[&stone].variants[""].models()[0].model,
+        "minecraft:block/stone".parse().unwrap()
+    );
+    assert!(models.contains_key(&"minecraft:block/stone".parse::<ResourceLocation>().unwrap()));
+
+    let texture = read_texture_image(&"block/stone".parse().unwrap(), zip.as_ref()).unwrap();
+    assert_eq!(texture.dimensions(), (2, 2));
+    assert!(read_texture_image(&"block/dirt".parse().unwrap(), zip.as_ref()).is_err());
+}